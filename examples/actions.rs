@@ -0,0 +1,61 @@
+//! Actions declaring their own bindings with `#[derive(Actions)]`, listed with their display metadata.
+use bevy::prelude::{App, Commands, DefaultPlugins, Query};
+use ezinput::prelude::*;
+
+#[derive(Actions, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum PlayerAction {
+    /// Jumps, or climbs when next to a ledge.
+    #[bind(KeyCode::Space, GamepadButtonType::South)]
+    Jump,
+    #[action(category = "Movement")]
+    Movement(MovementAction),
+}
+
+#[derive(Actions, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum MovementAction {
+    /// Moves the player around.
+    #[bind(axis_pair = DualAxis::wasd(), normalization = AxisPairNormalization::Circle)]
+    #[bind(axis_pair = DualAxis::left_stick())]
+    Move,
+    /// Dashes forward.
+    #[action(name = "Quick Dash")]
+    #[bind(KeyCode::LShift, GamepadButtonType::East)]
+    Dash,
+}
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugin(EZInputPlugin::<PlayerAction>::default())
+        .add_startup_system(spawn_player)
+        .add_system(check_input)
+        .run();
+}
+
+fn spawn_player(mut commands: Commands) {
+    for action in PlayerAction::all() {
+        println!(
+            "[{}] {}: {}",
+            action.category().unwrap_or("General"),
+            action.display_name(),
+            action.description().unwrap_or_default()
+        );
+    }
+    commands.spawn_bundle(InputHandlingBundle::new(PlayerAction::view()));
+}
+
+fn check_input(query: Query<&InputView<PlayerAction>>) {
+    use MovementAction::*;
+    use PlayerAction::*;
+
+    let view = query.single();
+    for action in [Jump, Movement(Dash)] {
+        if view.just_pressed(&action) {
+            println!("{:?} => {}", view.last_input_source, action.display_name());
+        }
+    }
+    let movement = view.axis_pair(&Movement(Move));
+    if movement != bevy::math::Vec2::ZERO {
+        println!("{:?} => Move: {:?}", view.last_input_source, movement);
+    }
+}
@@ -0,0 +1,32 @@
+//! A global input view, read as a resource without spawning any entity.
+use bevy::prelude::{App, DefaultPlugins, Res};
+use ezinput::prelude::*;
+
+input! {
+    MenuBinding {
+        Menu<MenuActionBinding> {
+            Pause = [KeyCode::Escape, GamepadButtonType::Start],
+            Confirm = [KeyCode::Return, GamepadButtonType::South, MouseButton::Left],
+        }
+    }
+}
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugin(EZInputPlugin::global_with(MenuBinding::view()))
+        .add_system(check_input)
+        .run();
+}
+
+fn check_input(view: Res<InputView<MenuBinding>>) {
+    use MenuActionBinding::*;
+    use MenuBinding::*;
+
+    if view.just_pressed(&Menu(Pause)) {
+        println!("{:?} => Pause", view.last_input_source);
+    }
+    if view.just_pressed(&Menu(Confirm)) {
+        println!("{:?} => Confirm", view.last_input_source);
+    }
+}
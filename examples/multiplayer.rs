@@ -1,4 +1,5 @@
 //! Keyboard assigned for player 1, and controller assigned for player 2
+#![allow(clippy::forget_non_drop)]
 
 use bevy::prelude::{App, Bundle, Commands, Component, DefaultPlugins, Query, With};
 use ezinput::prelude::{InputReceiver::*, *};
@@ -75,11 +76,11 @@ fn check_input(query: Query<(&EnumeratedInputView, &Name), With<Player>>) {
         if let Some(vertical) = view.axis(&Movement(Vertical)).first() {
             let action = if vertical.value < 0. { "Down" } else { "Up" };
 
-            if vertical.press.just_pressed() {
+            if view.just_pressed(&Movement(Vertical)) {
                 println!("({name}) {:?} => {action}", view.last_input_source);
             }
 
-            if let Some(elapsed) = vertical.press.elapsed() {
+            if let Some(elapsed) = view.elapsed(&Movement(Vertical)) {
                 println!(
                     "({name}) {:?} => {action} for {:?}",
                     view.last_input_source, elapsed
@@ -1,3 +1,5 @@
+#![allow(clippy::forget_non_drop)]
+
 use bevy::prelude::{App, Bundle, Commands, Component, DefaultPlugins, Query, With};
 use ezinput::prelude::*;
 
@@ -9,7 +11,9 @@ input! {
             Horizontal = [KeyCode::A => -1. /* default axis value */, KeyCode::D, GamepadAxisType::LeftStickX],
             Hello = [MouseAxisType::Wheel],
             Hi = [(MouseAxisType::X, MouseAxisDelta(MouseAxisType::X))],
-            Combination = [(KeyCode::E, MouseButton::Left)]
+            Combination = [(KeyCode::E, MouseButton::Left)],
+            Dodge = [] sequence(bevy::utils::Duration::from_millis(300)) [KeyCode::Down, KeyCode::Down],
+            Move = [] axis_pair [DualAxis::arrows(), DualAxis::right_stick()] => AxisPairNormalization::Circle,
         }
     }
 }
@@ -71,6 +75,13 @@ fn check_input(query: Query<&EnumeratedInputView, With<Player>>) {
             println!("{:?} => {action}: {:?}", view.last_input_source, axis.value);
         }
     }
+    if view.just_pressed(&Movement(Dodge)) {
+        println!("{:?} => Dodge", view.last_input_source);
+    }
+    let movement = view.axis_pair(&Movement(Move));
+    if movement != bevy::math::Vec2::ZERO {
+        println!("{:?} => Move: {:?}", view.last_input_source, movement);
+    }
     if let Some(axis) = view.axis(&Movement(Hello)).first() {
         if axis.pressed() {
             println!("Mouse => Wheel: {:?}", axis.value);
@@ -1,13 +1,428 @@
 extern crate proc_macro;
 
 use proc_macro::TokenStream;
-use syn::{parse_macro_input, DeriveInput};
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, quote_spanned};
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input,
+    punctuated::Punctuated,
+    spanned::Spanned,
+    Attribute, Data, DeriveInput, Expr, Fields, Ident, Lit, Meta, MetaNameValue, Token, Type,
+    Variant,
+};
 
-#[proc_macro_derive(BindingTypeView)]
+/// Derive `BindingTypeView` for an enumeration of actions, enumerating its unit variants and the actions of the
+/// enumerations nested in its single field variants, along with their metadata as described by the trait. Other
+/// types keep the default methods.
+#[proc_macro_derive(BindingTypeView, attributes(action))]
 pub fn derive_binding_type_view(_item: TokenStream) -> TokenStream {
-    let struct_name = &parse_macro_input!(_item as DeriveInput).ident;
-    let code = quote::quote! {
-        impl BindingTypeView for #struct_name {}
+    let input = parse_macro_input!(_item as DeriveInput);
+    expand_binding_type_view(&input, quote!(BindingTypeView))
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derive `BindingTypeView` and `Actions` for an enumeration of actions, along with a `view()` function creating
+/// an input view with the bindings of every action.
+///
+/// Unit variants are bound with any number of `#[bind(...)]` attributes. Each attribute adds a receiver
+/// combination per receiver expression, and takes the optional `default = <f32>`, `axis_pair = <DualAxis>`,
+/// `normalization = <AxisPairNormalization>` and `sequence = <Sequence>` options. Variants with a single field
+/// nest another enumeration deriving `Actions`, whose actions are bound wrapped into the variant. Metadata is
+/// derived as with the `BindingTypeView` derive.
+///
+/// ```ignore
+/// #[derive(Actions, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+/// enum Controls {
+///     /// Jumps.
+///     #[bind(KeyCode::Space, GamepadButtonType::South)]
+///     Jump,
+///     Movement(MovementControls),
+/// }
+///
+/// #[derive(Actions, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+/// enum MovementControls {
+///     #[bind(KeyCode::W, GamepadAxis(GamepadAxisType::LeftStickY))]
+///     #[bind(KeyCode::S, default = -1.0)]
+///     Vertical,
+///     #[bind(axis_pair = DualAxis::wasd(), normalization = AxisPairNormalization::Circle)]
+///     Move,
+/// }
+/// ```
+#[proc_macro_derive(Actions, attributes(bind, action))]
+pub fn derive_actions(item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    expand_actions(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// The options of a single `#[bind(...)]` attribute.
+#[derive(Default)]
+struct Bind {
+    receivers: Vec<Expr>,
+    default: Option<Expr>,
+    axis_pairs: Vec<Expr>,
+    normalization: Option<Expr>,
+    sequence: Option<Expr>,
+}
+
+enum BindItem {
+    Receiver(Expr),
+    Option(Ident, Expr),
+}
+
+impl Parse for BindItem {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.peek(Ident) && input.peek2(Token![=]) && !input.peek2(Token![==]) {
+            let key: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            return Ok(Self::Option(key, input.parse()?));
+        }
+        input.parse().map(Self::Receiver)
+    }
+}
+
+impl Parse for Bind {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut bind = Self::default();
+        for item in Punctuated::<BindItem, Token![,]>::parse_terminated(input)? {
+            match item {
+                BindItem::Receiver(expr) => bind.receivers.push(expr),
+                BindItem::Option(key, value) => {
+                    let slot = match key.to_string().as_str() {
+                        "default" => &mut bind.default,
+                        "normalization" => &mut bind.normalization,
+                        "sequence" => &mut bind.sequence,
+                        "axis_pair" => {
+                            bind.axis_pairs.push(value);
+                            continue;
+                        }
+                        _ => {
+                            return Err(syn::Error::new(
+                                key.span(),
+                                format!(
+                                    "unknown bind option `{}`, expected `default`, `axis_pair`, \
+                                     `normalization` or `sequence`",
+                                    key
+                                ),
+                            ))
+                        }
+                    };
+                    if slot.is_some() {
+                        return Err(syn::Error::new(
+                            key.span(),
+                            format!("duplicate bind option `{}`", key),
+                        ));
+                    }
+                    *slot = Some(value);
+                }
+            }
+        }
+        Ok(bind)
+    }
+}
+
+fn expand_actions(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let data = match &input.data {
+        Data::Enum(data) => data,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                name,
+                "`Actions` can only be derived for enums",
+            ))
+        }
     };
-    TokenStream::from(code)
+    let bindings = collect(
+        data.variants
+            .iter()
+            .map(|variant| expand_variant(name, variant)),
+    )?;
+    let binding_type_view =
+        expand_binding_type_view(input, quote!(::ezinput::prelude::BindingTypeView))?;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        #binding_type_view
+
+        impl #impl_generics ::ezinput::prelude::Actions for #name #ty_generics #where_clause {
+            #[allow(unused_imports)]
+            fn add_bindings<Keys>(
+                view: &mut ::ezinput::prelude::InputView<Keys>,
+                wrap: &dyn Fn(Self) -> Keys,
+            ) where
+                Keys: ::ezinput::prelude::BindingTypeView,
+            {
+                use ::ezinput::prelude::*;
+                #(#bindings)*
+            }
+        }
+
+        impl #impl_generics #name #ty_generics #where_clause {
+            /// Creates an input view with the bindings of every action.
+            pub fn view() -> ::ezinput::prelude::InputView<Self> {
+                let mut view = ::ezinput::prelude::InputView::new();
+                <Self as ::ezinput::prelude::Actions>::add_bindings(&mut view, &|action| action);
+                view
+            }
+        }
+    })
+}
+
+fn expand_variant(name: &Ident, variant: &Variant) -> syn::Result<TokenStream2> {
+    let ident = &variant.ident;
+    let binds: Vec<&Attribute> = variant
+        .attrs
+        .iter()
+        .filter(|attr| attr.path.is_ident("bind"))
+        .collect();
+    match &variant.fields {
+        Fields::Unit => {
+            let mut statements = Vec::new();
+            for attr in binds {
+                statements.push(expand_bind(&attr.parse_args()?));
+            }
+            Ok(quote! {
+                {
+                    let mut binding = ActionBinding::from(wrap(#name::#ident));
+                    #(#statements)*
+                    view.add_binding(&mut binding);
+                }
+            })
+        }
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+            if let Some(attr) = binds.first() {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "nested actions can't be bound, bind the variants of the nested enum instead",
+                ));
+            }
+            let ty = &fields.unnamed[0].ty;
+            Ok(quote_spanned! {ty.span()=>
+                <#ty as ::ezinput::prelude::Actions>::add_bindings(view, &|action| wrap(#name::#ident(action)));
+            })
+        }
+        Fields::Unnamed(fields) => Err(syn::Error::new_spanned(
+            fields,
+            "nested actions must have a single field with the nested enum",
+        )),
+        Fields::Named(_) => Err(syn::Error::new_spanned(
+            variant,
+            "actions must be unit variants or nest another enum deriving `Actions`",
+        )),
+    }
+}
+
+fn expand_bind(bind: &Bind) -> TokenStream2 {
+    let receivers = bind.receivers.iter().map(|rcv| {
+        let default = bind.default.as_ref().map(|default| {
+            quote! {
+                for rcv in (#rcv).into_receiver_vec() {
+                    binding.default_axis_value(rcv, #default);
+                }
+            }
+        });
+        quote_spanned! {rcv.span()=>
+            binding.receivers(InputReceivers::from((#rcv).into_receiver_vec()));
+            #default
+        }
+    });
+    let axis_pairs = bind.axis_pairs.iter();
+    let normalization = bind.normalization.iter();
+    let sequence = bind.sequence.iter();
+    quote! {
+        #(#receivers)*
+        #(binding.dual_axis(#axis_pairs);)*
+        #(binding.axis_pair_normalization(#normalization);)*
+        #(binding.sequence(#sequence);)*
+    }
+}
+
+/// Collect the results of every variant, combining their errors so they are reported at once.
+fn collect<T>(results: impl Iterator<Item = syn::Result<T>>) -> syn::Result<Vec<T>> {
+    let mut values = Vec::new();
+    let mut errors: Option<syn::Error> = None;
+    for result in results {
+        match result {
+            Ok(value) => values.push(value),
+            Err(error) => match errors.as_mut() {
+                Some(errors) => errors.combine(error),
+                None => errors = Some(error),
+            },
+        }
+    }
+    match errors {
+        Some(errors) => Err(errors),
+        None => Ok(values),
+    }
+}
+
+/// The metadata of a variant, with its `#[action(...)]` overrides.
+struct ActionMetadata {
+    name: String,
+    description: Option<String>,
+    category: Option<String>,
+}
+
+impl ActionMetadata {
+    fn parse(variant: &Variant) -> syn::Result<Self> {
+        let mut metadata = Self {
+            name: display_name(&variant.ident.to_string()),
+            description: None,
+            category: None,
+        };
+        let mut docs = Vec::new();
+        for attr in variant.attrs.iter() {
+            if attr.path.is_ident("doc") {
+                if let Ok(Meta::NameValue(MetaNameValue {
+                    lit: Lit::Str(doc), ..
+                })) = attr.parse_meta()
+                {
+                    docs.push(doc.value().trim().to_string());
+                }
+            } else if attr.path.is_ident("action") {
+                let options =
+                    attr.parse_args_with(Punctuated::<MetaNameValue, Token![,]>::parse_terminated)?;
+                for option in options {
+                    let value = match &option.lit {
+                        Lit::Str(value) => value.value(),
+                        lit => return Err(syn::Error::new_spanned(lit, "expected a string")),
+                    };
+                    if option.path.is_ident("name") {
+                        metadata.name = value;
+                    } else if option.path.is_ident("category") {
+                        metadata.category = Some(value);
+                    } else {
+                        return Err(syn::Error::new_spanned(
+                            &option.path,
+                            "unknown action option, expected `name` or `category`",
+                        ));
+                    }
+                }
+            }
+        }
+        let description = docs.join("\n");
+        if !description.trim().is_empty() {
+            metadata.description = Some(description.trim().to_string());
+        }
+        Ok(metadata)
+    }
+}
+
+/// Split a variant name into words, e.g. `MoveLeft` into `Move Left`.
+fn display_name(ident: &str) -> String {
+    let mut name = String::new();
+    let mut previous: Option<char> = None;
+    for c in ident.chars() {
+        if c == '_' {
+            name.push(' ');
+        } else {
+            if c.is_uppercase()
+                && previous.is_some_and(|previous| previous.is_lowercase() || previous.is_numeric())
+            {
+                name.push(' ');
+            }
+            name.push(c);
+        }
+        previous = Some(c);
+    }
+    name
+}
+
+/// Returns the type nested in a single field variant, if any.
+fn nested_type(variant: &Variant) -> Option<&Type> {
+    match &variant.fields {
+        Fields::Unnamed(fields) if fields.unnamed.len() == 1 => Some(&fields.unnamed[0].ty),
+        _ => None,
+    }
+}
+
+fn expand_binding_type_view(input: &DeriveInput, path: TokenStream2) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    // Keys that can't be enumerated keep the default methods.
+    let data = match &input.data {
+        Data::Enum(data)
+            if data.variants.iter().all(|variant| {
+                matches!(variant.fields, Fields::Unit) || nested_type(variant).is_some()
+            }) =>
+        {
+            data
+        }
+        _ => return Ok(quote!(impl #impl_generics #path for #name #ty_generics #where_clause {})),
+    };
+    let variants = collect(data.variants.iter().map(|variant| {
+        let metadata = ActionMetadata::parse(variant)?;
+        Ok((variant, metadata, nested_type(variant)))
+    }))?;
+
+    let mut all = Vec::new();
+    let mut names = Vec::new();
+    let mut descriptions = Vec::new();
+    let mut categories = Vec::new();
+    for (variant, metadata, nested) in variants {
+        let ident = &variant.ident;
+        let description = match &metadata.description {
+            Some(description) => quote!(Some(#description)),
+            None => quote!(None),
+        };
+        let category = match &metadata.category {
+            Some(category) => quote!(Some(#category)),
+            None => quote!(None),
+        };
+        match nested {
+            None => {
+                let display_name = &metadata.name;
+                all.push(quote!(.chain(::std::iter::once(#name::#ident))));
+                names.push(quote! {
+                    #name::#ident => ::std::borrow::Cow::Borrowed(#display_name),
+                });
+                descriptions.push(quote!(#name::#ident => #description,));
+                categories.push(quote!(#name::#ident => #category,));
+            }
+            Some(ty) => {
+                let group = metadata.category.as_ref().unwrap_or(&metadata.name);
+                all.push(quote_spanned! {ty.span()=>
+                    .chain(<#ty as #path>::all().into_iter().map(#name::#ident))
+                });
+                names.push(quote! {
+                    #name::#ident(action) => <#ty as #path>::display_name(&action),
+                });
+                descriptions.push(quote! {
+                    #name::#ident(action) => <#ty as #path>::description(&action),
+                });
+                categories.push(quote! {
+                    #name::#ident(action) => <#ty as #path>::category(&action).or(Some(#group)),
+                });
+            }
+        }
+    }
+
+    Ok(quote! {
+        impl #impl_generics #path for #name #ty_generics #where_clause {
+            fn all() -> ::std::vec::Vec<Self> {
+                ::std::iter::empty() #(#all)* .collect()
+            }
+
+            fn display_name(&self) -> ::std::borrow::Cow<'static, str> {
+                match *self {
+                    #(#names)*
+                }
+            }
+
+            fn description(&self) -> ::std::option::Option<&'static str> {
+                match *self {
+                    #(#descriptions)*
+                }
+            }
+
+            fn category(&self) -> ::std::option::Option<&'static str> {
+                match *self {
+                    #(#categories)*
+                }
+            }
+        }
+    })
 }
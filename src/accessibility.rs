@@ -0,0 +1,237 @@
+//! Accessibility options, changing how an action is pressed without changing the game code reading it.
+//!
+//! A toggled action is pressed by pressing its receivers once and released by pressing them again, instead of
+//! being held. A sticky action latches the modifiers of its receiver combinations, that is, every receiver but
+//! the last one, so `Ctrl + S` can be pressed by pressing and releasing Ctrl and then pressing S. The latched
+//! modifiers are cleared once the chord is released, or when the last receiver of a combination is pressed
+//! without completing it. The options are evaluated once per tick by the plugin, and [`InputView`] reports the
+//! resulting state for the action.
+use bevy::prelude::{Query, ResMut, SystemLabel};
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+
+#[derive(SystemLabel, Clone, Hash, Debug, PartialEq, Eq)]
+pub struct AccessibilitySystem;
+
+/// The accessibility options of a binding.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default, Deserialize, Serialize)]
+pub struct AccessibilityOptions {
+    /// Whether the action is toggled by each press instead of being held.
+    #[serde(default)]
+    pub toggle: bool,
+    /// Whether the modifiers of the receiver combinations stay latched after being released.
+    #[serde(default)]
+    pub sticky: bool,
+}
+
+impl AccessibilityOptions {
+    /// Returns whether any option is enabled.
+    pub fn enabled(&self) -> bool {
+        self.toggle || self.sticky
+    }
+
+    /// Returns whether every option is disabled, used to skip the options when serializing a profile.
+    pub fn is_default(&self) -> bool {
+        !self.enabled()
+    }
+}
+
+/// The runtime state of the accessibility options of a binding.
+#[derive(PartialEq, Clone, Debug)]
+pub(crate) struct AccessibilityState {
+    /// The state of the receiver combinations, with the latched modifiers of sticky bindings.
+    chord: AxisState,
+    /// The state reported for the action.
+    state: AxisState,
+    latched: Vec<InputReceiver>,
+}
+
+impl Default for AccessibilityState {
+    fn default() -> Self {
+        Self {
+            chord: AxisState::ZERO,
+            state: AxisState::ZERO,
+            latched: Vec::new(),
+        }
+    }
+}
+
+impl<Keys> ActionBinding<Keys>
+where
+    Keys: BindingTypeView,
+{
+    /// Set whether this action is toggled by each press instead of being held.
+    pub fn toggle(&mut self, toggle: bool) -> &mut Self {
+        self.accessibility.toggle = toggle;
+        self
+    }
+
+    /// Set whether the modifiers of the receiver combinations of this action stay latched after being released.
+    pub fn sticky(&mut self, sticky: bool) -> &mut Self {
+        self.accessibility.sticky = sticky;
+        self
+    }
+}
+
+impl<Keys> InputView<Keys>
+where
+    Keys: BindingTypeView,
+{
+    /// Returns the state of the given key resulting from its accessibility options, if any is enabled.
+    pub(crate) fn assisted_state(&self, kind: &Keys) -> Option<AxisState> {
+        if !self.bindings.get(kind)?.accessibility.enabled() {
+            return None;
+        }
+        Some(
+            self.accessibility_states
+                .get(kind)
+                .map_or(AxisState::ZERO, |assisted| assisted.state),
+        )
+    }
+
+    /// Evaluate the accessibility options of every binding for the current tick.
+    pub(crate) fn update_accessibility(&mut self) {
+        let (tick, time) = (self.tick, self.time);
+        for key in self.binding_order.clone() {
+            let binding = match self.bindings.get(&key) {
+                Some(binding) if binding.accessibility.enabled() => binding,
+                _ => {
+                    self.accessibility_states.remove(&key);
+                    continue;
+                }
+            };
+            let options = binding.accessibility;
+            let mut assisted = self.accessibility_states.remove(&key).unwrap_or_default();
+
+            if options.sticky {
+                for rcvs in binding.input_receivers.iter() {
+                    let modifiers = &rcvs.0[..rcvs.0.len().saturating_sub(1)];
+                    for rcv in modifiers {
+                        if self.state(rcv).pressed_on(tick) && !assisted.latched.contains(rcv) {
+                            assisted.latched.push(*rcv);
+                        }
+                    }
+                }
+            }
+            let value = binding.input_receivers.iter().find_map(|rcvs| {
+                let (last, modifiers) = rcvs.0.split_last()?;
+                if self.is_suppressed(&key, rcvs, |state| state.press.pressed()) {
+                    return None;
+                }
+                let held = |rcv: &InputReceiver| {
+                    self.state(rcv).press.pressed()
+                        || (options.sticky && assisted.latched.contains(rcv))
+                };
+                let last_state = self.state(last);
+                (last_state.press.pressed() && modifiers.iter().all(held))
+                    .then(|| binding.process_receiver(last, last_state.value))
+            });
+
+            let pressed = PressState::Pressed {
+                started_pressing_instant: Some(time),
+            };
+            match value {
+                Some(value) => assisted.chord.update(value, pressed, tick),
+                None => {
+                    // A chord was released, or the last receiver of a combination was pressed without it.
+                    let is_modifier = |rcv: &InputReceiver| {
+                        binding
+                            .input_receivers
+                            .iter()
+                            .any(|rcvs| rcvs.0[..rcvs.0.len().saturating_sub(1)].contains(rcv))
+                    };
+                    let missed = binding.input_receivers.iter().any(|rcvs| {
+                        rcvs.0.last().is_some_and(|last| {
+                            self.state(last).pressed_on(tick) && !is_modifier(last)
+                        })
+                    });
+                    if assisted.chord.press.pressed() || missed {
+                        assisted.latched.clear();
+                    }
+                    assisted.chord.update(0., PressState::Released, tick);
+                }
+            }
+            if !options.toggle {
+                assisted.state = assisted.chord;
+            } else if assisted.chord.just_pressed_on(tick) {
+                if assisted.state.press.pressed() {
+                    assisted.state.update(0., PressState::Released, tick);
+                } else {
+                    assisted.state.update(assisted.chord.value, pressed, tick);
+                }
+            }
+            self.accessibility_states.insert(key, assisted);
+        }
+    }
+}
+
+/// Evaluate the accessibility options of every view.
+pub(crate) fn accessibility_system<Keys>(
+    mut query: Query<&mut InputView<Keys>>,
+    global: Option<ResMut<InputView<Keys>>>,
+) where
+    Keys: BindingTypeView,
+{
+    for mut view in query.iter_mut() {
+        view.update_accessibility();
+    }
+    if let Some(mut view) = global {
+        view.update_accessibility();
+    }
+}
+
+// Test to check if toggled actions flip on each press and sticky modifiers stay latched until the chord is used
+// or missed.
+#[test]
+fn accessibility_test() {
+    use crate::harness::{ViewHarness, PRESSED};
+    use bevy::prelude::KeyCode;
+
+    #[derive(crate::BindingTypeView, PartialEq, Eq, Hash, Clone, Copy, Debug)]
+    enum Action {
+        Sprint,
+        Save,
+    }
+    let (shift, ctrl, s, alt, d) = (
+        InputReceiver::KeyboardKey(KeyCode::LShift),
+        InputReceiver::KeyboardKey(KeyCode::LControl),
+        InputReceiver::KeyboardKey(KeyCode::S),
+        InputReceiver::KeyboardKey(KeyCode::LAlt),
+        InputReceiver::KeyboardKey(KeyCode::D),
+    );
+    let mut view = InputView::new();
+    view.add_binding(ActionBinding::new_from_vec(Action::Sprint, vec![vec![shift]]).toggle(true));
+    view.add_binding(
+        ActionBinding::new_from_vec(Action::Save, vec![vec![ctrl, s], vec![alt, d]]).sticky(true),
+    );
+
+    let mut harness = ViewHarness::new(view, accessibility_system::<Action>);
+
+    harness.tick(0, &[(shift, PRESSED)]);
+    harness.tick(0, &[(shift, PressState::Released)]);
+    let view = harness.view();
+    assert!(view.key(&Action::Sprint).pressed());
+    harness.tick(0, &[(shift, PRESSED)]);
+    let view = harness.view();
+    assert!(view.just_released(&Action::Sprint));
+
+    harness.tick(0, &[(ctrl, PRESSED)]);
+    harness.tick(0, &[(ctrl, PressState::Released)]);
+    let view = harness.view();
+    assert!(view.key(&Action::Save).released());
+    harness.tick(0, &[(s, PRESSED)]);
+    let view = harness.view();
+    assert!(view.just_pressed(&Action::Save));
+    harness.tick(0, &[(s, PressState::Released)]);
+    harness.tick(0, &[(s, PRESSED)]);
+    let view = harness.view();
+    assert!(view.key(&Action::Save).released());
+
+    harness.tick(0, &[(s, PressState::Released), (ctrl, PRESSED)]);
+    harness.tick(0, &[(ctrl, PressState::Released)]);
+    harness.tick(0, &[(d, PRESSED)]);
+    harness.tick(0, &[(d, PressState::Released), (s, PRESSED)]);
+    let view = harness.view();
+    assert!(view.key(&Action::Save).released());
+}
@@ -0,0 +1,107 @@
+//! Enumerated actions declaring their own bindings, implemented with `#[derive(Actions)]`.
+//!
+//! The derive is an alternative to the [`crate::input`] macro: variants are bound with `#[bind(...)]` attributes,
+//! keep their doc comments and attributes, and may nest other enumerations deriving [`Actions`] at any depth.
+//! Mistakes in the attributes are reported at the offending variant, such as unknown options:
+//!
+//! ```compile_fail
+//! # use ezinput::prelude::*;
+//! #[derive(Actions, PartialEq, Eq, Hash, Clone, Copy, Debug)]
+//! enum Controls {
+//!     #[bind(KeyCode::Space, dead_zone = 0.1)]
+//!     Jump,
+//! }
+//! ```
+//!
+//! Options given twice in the same attribute:
+//!
+//! ```compile_fail
+//! # use ezinput::prelude::*;
+//! #[derive(Actions, PartialEq, Eq, Hash, Clone, Copy, Debug)]
+//! enum Controls {
+//!     #[bind(KeyCode::S, default = -1.0, default = 1.0)]
+//!     Vertical,
+//! }
+//! ```
+//!
+//! Bindings of nested actions, which must be bound in the nested enumeration:
+//!
+//! ```compile_fail
+//! # use ezinput::prelude::*;
+//! #[derive(Actions, PartialEq, Eq, Hash, Clone, Copy, Debug)]
+//! enum Controls {
+//!     #[bind(KeyCode::Space)]
+//!     Movement(MovementControls),
+//! }
+//!
+//! #[derive(Actions, PartialEq, Eq, Hash, Clone, Copy, Debug)]
+//! enum MovementControls {
+//!     #[bind(KeyCode::W)]
+//!     Forward,
+//! }
+//! ```
+//!
+//! And variants with named fields:
+//!
+//! ```compile_fail
+//! # use ezinput::prelude::*;
+//! #[derive(Actions, PartialEq, Eq, Hash, Clone, Copy, Debug)]
+//! enum Controls {
+//!     Jump { height: u8 },
+//! }
+//! ```
+use crate::prelude::*;
+
+/// A trait implemented by `#[derive(Actions)]` for enumerated action keys, which also implements
+/// [`BindingTypeView`] and a `view()` function creating an input view with the bindings of every action.
+pub trait Actions: BindingTypeView {
+    /// Add the bindings of every action to the given view, wrapping each action into the keys of the view.
+    fn add_bindings<Keys>(view: &mut InputView<Keys>, wrap: &dyn Fn(Self) -> Keys)
+    where
+        Keys: BindingTypeView;
+}
+
+// Test to check if derived actions are bound from their attributes, including the actions of nested enums.
+#[test]
+fn actions_derive_test() {
+    #[derive(crate::Actions, PartialEq, Eq, Hash, Clone, Copy, Debug)]
+    enum Controls {
+        /// Jumps, with either the keyboard or a gamepad.
+        #[bind(KeyCode::Space, GamepadButtonType::South)]
+        Jump,
+        Movement(MovementControls),
+    }
+
+    #[derive(crate::Actions, PartialEq, Eq, Hash, Clone, Copy, Debug)]
+    enum MovementControls {
+        #[bind(KeyCode::W, GamepadAxis(GamepadAxisType::LeftStickY))]
+        #[bind(KeyCode::S, default = -1.0)]
+        Vertical,
+        #[bind(axis_pair = DualAxis::wasd(), normalization = AxisPairNormalization::Circle)]
+        Move,
+    }
+
+    let view = Controls::view();
+    assert_eq!(
+        view.binding_order,
+        vec![
+            Controls::Jump,
+            Controls::Movement(MovementControls::Vertical),
+            Controls::Movement(MovementControls::Move),
+        ]
+    );
+    assert_eq!(view.bindings[&Controls::Jump].input_receivers.len(), 2);
+    let vertical = &view.bindings[&Controls::Movement(MovementControls::Vertical)];
+    assert_eq!(vertical.input_receivers.len(), 3);
+    assert_eq!(
+        vertical.default_axis_value.get(&KeyCode::S.into()),
+        Some(&-1.)
+    );
+    let movement = &view.bindings[&Controls::Movement(MovementControls::Move)];
+    assert_eq!(movement.dual_axes, vec![DualAxis::wasd()]);
+    assert_eq!(
+        movement.axis_pair_normalization,
+        AxisPairNormalization::Circle
+    );
+    assert_eq!(MovementControls::view().binding_order.len(), 2);
+}
@@ -0,0 +1,298 @@
+//! Per-player gamepad assignment. Entities with an [`AwaitingGamepad`] component are assigned the first
+//! unclaimed gamepad whose join button is pressed, and players whose gamepad is disconnected get it back when it
+//! reconnects, or any gamepad depending on the [`ReconnectPolicy`]. The owner of each gamepad is kept in the
+//! [`GamepadAssignments`] resource.
+use bevy::{
+    prelude::{
+        Commands, Component, Entity, EventReader, EventWriter, Gamepad, GamepadButtonType,
+        GamepadEvent, GamepadEventType, Query, RemovedComponents, ResMut, SystemLabel,
+    },
+    utils::{HashMap, HashSet},
+};
+
+use crate::prelude::*;
+
+#[derive(SystemLabel, Clone, Hash, Debug, PartialEq, Eq)]
+pub struct GamepadAssignmentSystem;
+
+/// An entity waiting for a player to press the join button of an unclaimed gamepad.
+#[derive(PartialEq, Eq, Debug, Component, Clone, Copy)]
+pub struct AwaitingGamepad {
+    pub join_button: GamepadButtonType,
+}
+
+impl Default for AwaitingGamepad {
+    fn default() -> Self {
+        Self {
+            join_button: GamepadButtonType::Start,
+        }
+    }
+}
+
+/// An event sent when the gamepad of an entity changes.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum GamepadAssignmentEvent {
+    /// An awaiting entity was assigned a gamepad.
+    Joined { entity: Entity, gamepad: Gamepad },
+    /// The gamepad of an entity was disconnected. The entity keeps its gamepad until it is reassigned.
+    Disconnected { entity: Entity, gamepad: Gamepad },
+    /// An entity whose gamepad was disconnected was assigned a connected gamepad, which is a different one only
+    /// with [`ReconnectPolicy::AnyGamepad`].
+    Reconnected { entity: Entity, gamepad: Gamepad },
+}
+
+/// Which gamepad is assigned to a player whose gamepad was disconnected.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum ReconnectPolicy {
+    /// The player gets its gamepad back when the same gamepad reconnects.
+    #[default]
+    SameGamepad,
+    /// The player also takes the next gamepad connected without an owner, in the order players were disconnected.
+    AnyGamepad,
+}
+
+/// The owner of each gamepad and the connected gamepads.
+#[derive(PartialEq, Debug, Clone, Default)]
+pub struct GamepadAssignments {
+    pub reconnect_policy: ReconnectPolicy,
+    owners: HashMap<Gamepad, Entity>,
+    connected: HashSet<Gamepad>,
+    /// Entities whose gamepad is disconnected, in the order they were disconnected.
+    disconnected: Vec<Entity>,
+}
+
+impl GamepadAssignments {
+    /// Returns the entity owning the given gamepad.
+    pub fn owner(&self, gamepad: Gamepad) -> Option<Entity> {
+        self.owners.get(&gamepad).copied()
+    }
+
+    /// Returns the gamepad owned by the given entity.
+    pub fn gamepad(&self, entity: Entity) -> Option<Gamepad> {
+        self.owners
+            .iter()
+            .find(|(_, owner)| **owner == entity)
+            .map(|(gamepad, _)| *gamepad)
+    }
+
+    /// Returns every gamepad and its owner.
+    pub fn iter(&self) -> impl Iterator<Item = (Gamepad, Entity)> + '_ {
+        self.owners
+            .iter()
+            .map(|(gamepad, entity)| (*gamepad, *entity))
+    }
+
+    /// Returns whether the given gamepad is connected.
+    pub fn is_connected(&self, gamepad: Gamepad) -> bool {
+        self.connected.contains(&gamepad)
+    }
+
+    /// Returns every connected gamepad without an owner.
+    pub fn unclaimed(&self) -> impl Iterator<Item = Gamepad> + '_ {
+        self.connected
+            .iter()
+            .filter(|gamepad| !self.owners.contains_key(gamepad))
+            .copied()
+    }
+
+    /// Assign a gamepad to an entity, replacing the previous gamepad of the entity and the previous owner of the
+    /// gamepad. The [`GamepadMarker`] of the entity is updated by the assignment system.
+    pub fn assign(&mut self, gamepad: Gamepad, entity: Entity) {
+        self.unassign(entity);
+        self.owners.insert(gamepad, entity);
+    }
+
+    /// Remove the gamepad of an entity, returning it.
+    pub fn unassign(&mut self, entity: Entity) -> Option<Gamepad> {
+        self.disconnected.retain(|e| *e != entity);
+        let gamepad = self.gamepad(entity)?;
+        self.owners.remove(&gamepad);
+        Some(gamepad)
+    }
+}
+
+/// Track connected gamepads, assign gamepads to awaiting entities and disconnected players, and keep the
+/// [`GamepadMarker`] of every entity in sync with its assignment.
+pub(crate) fn gamepad_assignment_system(
+    mut commands: Commands,
+    mut assignments: ResMut<GamepadAssignments>,
+    awaiting: Query<(Entity, &AwaitingGamepad)>,
+    mut markers: Query<(Entity, &mut GamepadMarker)>,
+    removed: RemovedComponents<GamepadMarker>,
+    mut rd: EventReader<GamepadEvent>,
+    mut writer: EventWriter<GamepadAssignmentEvent>,
+) {
+    for entity in removed.iter() {
+        assignments.unassign(entity);
+    }
+    let mut joined: Vec<Entity> = Vec::new();
+    for GamepadEvent(gamepad, kind) in rd.iter() {
+        let gamepad = *gamepad;
+        match kind {
+            GamepadEventType::Connected => {
+                assignments.connected.insert(gamepad);
+                let entity = match assignments.owner(gamepad) {
+                    Some(entity) if assignments.disconnected.contains(&entity) => Some(entity),
+                    Some(_) => None,
+                    None if assignments.reconnect_policy == ReconnectPolicy::AnyGamepad => {
+                        assignments.disconnected.first().copied()
+                    }
+                    None => None,
+                };
+                if let Some(entity) = entity {
+                    assignments.assign(gamepad, entity);
+                    writer.send(GamepadAssignmentEvent::Reconnected { entity, gamepad });
+                }
+            }
+            GamepadEventType::Disconnected => {
+                assignments.connected.remove(&gamepad);
+                if let Some(entity) = assignments.owner(gamepad) {
+                    assignments.disconnected.push(entity);
+                    writer.send(GamepadAssignmentEvent::Disconnected { entity, gamepad });
+                }
+            }
+            GamepadEventType::ButtonChanged(button, value) => {
+                if *value < 0.5 || assignments.owner(gamepad).is_some() {
+                    continue;
+                }
+                let entity = awaiting
+                    .iter()
+                    .filter(|(entity, awaiting)| {
+                        awaiting.join_button == *button && !joined.contains(entity)
+                    })
+                    .map(|(entity, _)| entity)
+                    .min();
+                if let Some(entity) = entity {
+                    assignments.connected.insert(gamepad);
+                    assignments.assign(gamepad, entity);
+                    joined.push(entity);
+                    commands.entity(entity).remove::<AwaitingGamepad>();
+                    writer.send(GamepadAssignmentEvent::Joined { entity, gamepad });
+                }
+            }
+            GamepadEventType::AxisChanged(..) => {}
+        }
+    }
+    for (entity, mut marker) in markers.iter_mut() {
+        if let Some(gamepad) = assignments.gamepad(entity) {
+            if marker.gamepad != gamepad {
+                marker.gamepad = gamepad;
+            }
+        }
+    }
+}
+
+// Test to check if players join with the first pressed gamepad and get their gamepad back after a disconnection,
+// or a new one with the any gamepad policy.
+#[test]
+fn gamepad_assignment_test() {
+    use bevy::{
+        ecs::event::Events,
+        prelude::{Stage, SystemStage, World},
+    };
+
+    let mut world = World::new();
+    world.insert_resource(GamepadAssignments::default());
+    world.insert_resource(Events::<GamepadEvent>::default());
+    world.insert_resource(Events::<GamepadAssignmentEvent>::default());
+    let first = world
+        .spawn()
+        .insert(GamepadMarker::default())
+        .insert(AwaitingGamepad::default())
+        .id();
+    let second = world
+        .spawn()
+        .insert(GamepadMarker::default())
+        .insert(AwaitingGamepad::default())
+        .id();
+    let mut stage = SystemStage::single(gamepad_assignment_system);
+    let mut tick = |world: &mut World, events: Vec<GamepadEvent>| {
+        world.resource_mut::<Events<GamepadEvent>>().extend(events);
+        stage.run(world);
+        let mut events = world.resource_mut::<Events<GamepadAssignmentEvent>>();
+        let sent: Vec<_> = events.drain().collect();
+        sent
+    };
+    let start = GamepadEventType::ButtonChanged(GamepadButtonType::Start, 1.);
+
+    let sent = tick(
+        &mut world,
+        vec![
+            GamepadEvent(Gamepad(0), GamepadEventType::Connected),
+            GamepadEvent(Gamepad(1), GamepadEventType::Connected),
+            GamepadEvent(Gamepad(1), start),
+        ],
+    );
+    assert_eq!(
+        sent,
+        vec![GamepadAssignmentEvent::Joined {
+            entity: first,
+            gamepad: Gamepad(1)
+        }]
+    );
+    assert_eq!(
+        world.get::<GamepadMarker>(first).unwrap().gamepad,
+        Gamepad(1)
+    );
+    assert!(world.get::<AwaitingGamepad>(first).is_none());
+    assert!(world.get::<AwaitingGamepad>(second).is_some());
+    let assignments = world.resource::<GamepadAssignments>();
+    assert_eq!(
+        assignments.unclaimed().collect::<Vec<_>>(),
+        vec![Gamepad(0)]
+    );
+
+    tick(
+        &mut world,
+        vec![GamepadEvent(Gamepad(1), GamepadEventType::Disconnected)],
+    );
+    let sent = tick(
+        &mut world,
+        vec![GamepadEvent(Gamepad(2), GamepadEventType::Connected)],
+    );
+    assert!(sent.is_empty());
+    assert_eq!(
+        world.resource::<GamepadAssignments>().owner(Gamepad(2)),
+        None
+    );
+    let sent = tick(
+        &mut world,
+        vec![GamepadEvent(Gamepad(1), GamepadEventType::Connected)],
+    );
+    assert_eq!(
+        sent,
+        vec![GamepadAssignmentEvent::Reconnected {
+            entity: first,
+            gamepad: Gamepad(1)
+        }]
+    );
+
+    world.resource_mut::<GamepadAssignments>().reconnect_policy = ReconnectPolicy::AnyGamepad;
+    tick(
+        &mut world,
+        vec![GamepadEvent(Gamepad(1), GamepadEventType::Disconnected)],
+    );
+    let sent = tick(
+        &mut world,
+        vec![GamepadEvent(Gamepad(3), GamepadEventType::Connected)],
+    );
+    assert_eq!(
+        sent,
+        vec![GamepadAssignmentEvent::Reconnected {
+            entity: first,
+            gamepad: Gamepad(3)
+        }]
+    );
+    assert_eq!(
+        world.get::<GamepadMarker>(first).unwrap().gamepad,
+        Gamepad(3)
+    );
+    assert_eq!(
+        world.resource::<GamepadAssignments>().owner(Gamepad(3)),
+        Some(first)
+    );
+    assert_eq!(
+        world.resource::<GamepadAssignments>().owner(Gamepad(1)),
+        None
+    );
+}
@@ -3,12 +3,36 @@
 use crate::prelude::*;
 use bevy::utils::HashSet;
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, fmt::Debug, hash::Hash};
+use std::{borrow::Cow, collections::HashMap, fmt::Debug, hash::Hash};
 
 /// A trait to be implemented for enumerated action keys.
+///
+/// The derive implements the metadata below from the variants, for building controls menus: the display name
+/// defaults to the variant name split into words, the description to its doc comment, and the category of nested
+/// actions to the name of the variant nesting them. They can be set with `#[action(name = "...", category = "...")]`.
+/// Keys that aren't enums of unit variants and nested enums keep the default methods.
 pub trait BindingTypeView:
     PartialEq + Eq + Hash + Clone + Copy + Debug + Send + Sync + 'static
 {
+    /// Returns every action, including the actions of nested enumerations, in declaration order.
+    fn all() -> Vec<Self> {
+        Vec::new()
+    }
+
+    /// Returns the human-readable name of this action, its debug representation unless derived.
+    fn display_name(&self) -> Cow<'static, str> {
+        Cow::Owned(format!("{:?}", self))
+    }
+
+    /// Returns the description of this action, if any.
+    fn description(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// Returns the menu category of this action, if any.
+    fn category(&self) -> Option<&'static str> {
+        None
+    }
 }
 
 #[derive(PartialEq, Eq, Clone, Debug, Hash, Deserialize, Serialize, Default)]
@@ -81,6 +105,22 @@ where
     pub key: InputKey,
     pub input_receivers: HashSet<InputReceivers>,
     pub default_axis_value: HashMap<InputReceiver, f32>,
+    #[serde(default)]
+    pub dual_axes: Vec<DualAxis>,
+    #[serde(default)]
+    pub axis_pair_normalization: AxisPairNormalization,
+    #[serde(default)]
+    pub processors: Vec<AxisProcessor>,
+    #[serde(default)]
+    pub receiver_processors: HashMap<InputReceiver, Vec<AxisProcessor>>,
+    #[serde(default)]
+    pub sequences: Vec<Sequence>,
+    #[serde(default)]
+    pub buffer_window: Option<BufferWindow>,
+    #[serde(default)]
+    pub interactions: Vec<InputInteraction>,
+    #[serde(default)]
+    pub accessibility: AccessibilityOptions,
 }
 
 impl<InputKey> From<InputKey> for ActionBinding<InputKey>
@@ -93,6 +133,14 @@ where
             key,
             input_receivers: HashSet::default(),
             default_axis_value: HashMap::default(),
+            dual_axes: Vec::new(),
+            axis_pair_normalization: AxisPairNormalization::default(),
+            processors: Vec::new(),
+            receiver_processors: HashMap::default(),
+            sequences: Vec::new(),
+            buffer_window: None,
+            interactions: Vec::new(),
+            accessibility: AccessibilityOptions::default(),
         }
     }
 }
@@ -104,21 +152,19 @@ where
     /// Create a new action binding from a key and a list of input receivers.
     pub fn new(key: InputKey, receivers: HashSet<InputReceivers>) -> Self {
         Self {
-            key,
-            default_axis_value: HashMap::default(),
             input_receivers: receivers,
+            ..Self::from(key)
         }
     }
 
     /// Create a new action binding from a key and a non-converted list of input receivers.
     pub fn new_from_vec(key: InputKey, receiver: Vec<Vec<InputReceiver>>) -> Self {
         Self {
-            key,
-            default_axis_value: HashMap::default(),
             input_receivers: receiver
                 .iter()
                 .map(|vec| InputReceivers::from(vec.clone()))
                 .collect(),
+            ..Self::from(key)
         }
     }
 
@@ -139,12 +185,91 @@ where
         self
     }
 
-    /// Apply the default axis value for each registered receiver for a specific view.
+    /// Add a 2D axis source to this action, read with [`InputView::axis_pair`].
+    pub fn dual_axis(&mut self, source: DualAxis) -> &mut Self {
+        self.dual_axes.push(source);
+        self
+    }
+
+    /// Set how the 2D axis value of this action is limited.
+    pub fn axis_pair_normalization(&mut self, normalization: AxisPairNormalization) -> &mut Self {
+        self.axis_pair_normalization = normalization;
+        self
+    }
+
+    /// Apply the default axis value for each registered receiver for a specific view. The values are kept in
+    /// this binding, so they are not lost when the binding is exported again.
     pub fn apply_default_axis_to_all_receivers(&mut self, view: &mut InputView<InputKey>) -> &Self {
         for (input, value) in self.default_axis_value.iter() {
             view.descriptor_or_insert(*input).default_axis_value = *value;
         }
-        self.default_axis_value.clear();
         self
     }
 }
+
+// Test to check if derived action keys enumerate every action with its metadata, including nested actions.
+#[test]
+fn binding_type_view_metadata_test() {
+    #[derive(crate::BindingTypeView, PartialEq, Eq, Hash, Clone, Copy, Debug)]
+    enum Controls {
+        /// Opens the pause menu.
+        Pause,
+        Movement(MovementControls),
+        #[action(name = "Camera", category = "View")]
+        CameraControls(CameraControls),
+    }
+
+    #[derive(crate::BindingTypeView, PartialEq, Eq, Hash, Clone, Copy, Debug)]
+    enum MovementControls {
+        MoveLeft,
+        /// Jumps over obstacles,
+        /// or onto ledges.
+        #[action(name = "Jump!")]
+        Jump,
+    }
+
+    #[derive(crate::BindingTypeView, PartialEq, Eq, Hash, Clone, Copy, Debug)]
+    enum CameraControls {
+        #[action(category = "Zoom")]
+        ZoomIn,
+        Look,
+    }
+
+    assert_eq!(
+        Controls::all(),
+        vec![
+            Controls::Pause,
+            Controls::Movement(MovementControls::MoveLeft),
+            Controls::Movement(MovementControls::Jump),
+            Controls::CameraControls(CameraControls::ZoomIn),
+            Controls::CameraControls(CameraControls::Look),
+        ]
+    );
+    let jump = Controls::Movement(MovementControls::Jump);
+    assert_eq!(jump.display_name(), "Jump!");
+    assert_eq!(
+        jump.description(),
+        Some("Jumps over obstacles,\nor onto ledges.")
+    );
+    assert_eq!(jump.category(), Some("Movement"));
+    let move_left = Controls::Movement(MovementControls::MoveLeft);
+    assert_eq!(move_left.display_name(), "Move Left");
+    assert_eq!(move_left.description(), None);
+    assert_eq!(Controls::Pause.description(), Some("Opens the pause menu."));
+    assert_eq!(Controls::Pause.category(), None);
+    let look = Controls::CameraControls(CameraControls::Look);
+    assert_eq!(look.category(), Some("View"));
+    let zoom = Controls::CameraControls(CameraControls::ZoomIn);
+    assert_eq!(zoom.category(), Some("Zoom"));
+
+    #[derive(crate::BindingTypeView, PartialEq, Eq, Hash, Clone, Copy, Debug)]
+    enum Axes {
+        Stick { index: u8 },
+    }
+
+    assert!(Axes::all().is_empty());
+    assert_eq!(
+        Axes::Stick { index: 1 }.display_name(),
+        "Stick { index: 1 }"
+    );
+}
@@ -0,0 +1,165 @@
+//! Input buffering, letting an action pressed slightly before it can be performed (e.g. a jump pressed a few
+//! frames before landing) still be handled once it can.
+//!
+//! Every press of an action is remembered until it is consumed with [`InputView::consume`] or falls out of the
+//! [`BufferWindow`] of its binding, so a buffered press fires exactly once.
+use bevy::{
+    prelude::{Query, ResMut, SystemLabel},
+    utils::Duration,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+
+#[derive(SystemLabel, Clone, Hash, Debug, PartialEq, Eq)]
+pub struct InputBufferSystem;
+
+/// How long a press of an action stays buffered.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Deserialize, Serialize)]
+pub enum BufferWindow {
+    /// A duration measured by the [`crate::clock::InputClock`].
+    Time(Duration),
+    /// A number of ticks after the tick the action was pressed.
+    Ticks(u64),
+}
+
+impl Default for BufferWindow {
+    /// Presses are only buffered during the tick they happened.
+    fn default() -> Self {
+        BufferWindow::Ticks(0)
+    }
+}
+
+/// The tick and time an action was last pressed.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct BufferedPress {
+    pub tick: u64,
+    pub time: Duration,
+}
+
+impl BufferWindow {
+    /// Returns whether the given press is still inside this window.
+    pub fn contains(&self, press: &BufferedPress, tick: u64, time: Duration) -> bool {
+        match self {
+            BufferWindow::Time(duration) => time.saturating_sub(press.time) <= *duration,
+            BufferWindow::Ticks(ticks) => tick.saturating_sub(press.tick) <= *ticks,
+        }
+    }
+}
+
+impl<Keys> ActionBinding<Keys>
+where
+    Keys: BindingTypeView,
+{
+    /// Set how long a press of this action stays buffered.
+    pub fn buffer(&mut self, window: BufferWindow) -> &mut Self {
+        self.buffer_window = Some(window);
+        self
+    }
+}
+
+impl<Keys> InputView<Keys>
+where
+    Keys: BindingTypeView,
+{
+    /// Returns whether the action for the given key was pressed inside the buffer window of its binding and the
+    /// press wasn't consumed yet.
+    pub fn buffered(&self, kind: &Keys) -> bool {
+        let window = self
+            .bindings
+            .get(kind)
+            .and_then(|binding| binding.buffer_window)
+            .unwrap_or_default();
+        self.buffered_presses
+            .get(kind)
+            .is_some_and(|press| window.contains(press, self.tick, self.time))
+    }
+
+    /// Consume the buffered press of the action for the given key, returning whether it was buffered.
+    pub fn consume(&mut self, kind: &Keys) -> bool {
+        let buffered = self.buffered(kind);
+        self.buffered_presses.remove(kind);
+        buffered
+    }
+
+    /// Remember the actions pressed in the current tick, forgetting presses outside their buffer windows.
+    pub(crate) fn buffer_presses(&mut self) {
+        let (tick, time) = (self.tick, self.time);
+        let pressed: Vec<Keys> = self
+            .binding_order
+            .iter()
+            .filter(|key| self.pressed_this_frame(key))
+            .copied()
+            .collect();
+        for key in pressed {
+            self.buffered_presses
+                .insert(key, BufferedPress { tick, time });
+        }
+        let bindings = &self.bindings;
+        self.buffered_presses.retain(|key, press| {
+            bindings.get(key).is_some_and(|binding| {
+                binding
+                    .buffer_window
+                    .unwrap_or_default()
+                    .contains(press, tick, time)
+            })
+        });
+    }
+}
+
+/// Buffer the actions pressed in the current tick for every view.
+pub(crate) fn input_buffer_system<Keys>(
+    mut query: Query<&mut InputView<Keys>>,
+    global: Option<ResMut<InputView<Keys>>>,
+) where
+    Keys: BindingTypeView,
+{
+    for mut view in query.iter_mut() {
+        view.buffer_presses();
+    }
+    if let Some(mut view) = global {
+        view.buffer_presses();
+    }
+}
+
+// Test to check if a buffered press fires once inside its window and expires outside of it.
+#[test]
+fn input_buffer_test() {
+    use crate::harness::{ViewHarness, PRESSED};
+    use bevy::prelude::KeyCode;
+
+    #[derive(crate::BindingTypeView, PartialEq, Eq, Hash, Clone, Copy, Debug)]
+    enum Action {
+        Jump,
+        Dash,
+    }
+    let (space, shift) = (
+        InputReceiver::KeyboardKey(KeyCode::Space),
+        InputReceiver::KeyboardKey(KeyCode::LShift),
+    );
+    let mut view = InputView::new();
+    view.add_binding(
+        ActionBinding::new_from_vec(Action::Jump, vec![vec![space]])
+            .buffer(BufferWindow::Time(Duration::from_millis(100))),
+    );
+    view.add_binding(
+        ActionBinding::new_from_vec(Action::Dash, vec![vec![shift]]).buffer(BufferWindow::Ticks(2)),
+    );
+
+    let mut harness = ViewHarness::new(view, input_buffer_system::<Action>);
+
+    harness.tick(16, &[(space, PRESSED), (shift, PRESSED)]);
+    harness.tick(16, &[(space, PressState::Released)]);
+    assert!(harness.view().buffered(&Action::Jump));
+    assert!(harness.view_mut().consume(&Action::Jump));
+    assert!(!harness.view_mut().consume(&Action::Jump));
+    assert!(harness.view().buffered(&Action::Dash));
+
+    harness.tick(16, &[]);
+    harness.tick(16, &[]);
+    assert!(!harness.view().buffered(&Action::Dash));
+
+    harness.tick(16, &[(space, PRESSED)]);
+    harness.tick(120, &[]);
+    assert!(!harness.view_mut().consume(&Action::Jump));
+}
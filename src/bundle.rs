@@ -1,4 +1,5 @@
 //! Definition of a ECS component bundle providing an input view and all supported input markers.
+#![allow(clippy::forget_non_drop)]
 
 use bevy::prelude::Bundle;
 
@@ -13,15 +14,17 @@ where
     pub keyboard_input: KeyboardMarker,
     pub mouse_input: MouseMarker,
     pub gamepad_input: GamepadMarker,
+    pub touch_input: TouchMarker,
 }
 
 impl<Keys: BindingTypeView> InputHandlingBundle<Keys> {
     pub fn new(input: InputView<Keys>) -> Self {
         Self {
             input,
-            keyboard_input: KeyboardMarker::default(),
+            keyboard_input: KeyboardMarker,
             mouse_input: MouseMarker::default(),
             gamepad_input: GamepadMarker::default(),
+            touch_input: TouchMarker::default(),
         }
     }
     pub fn with_deadzone(input: InputView<Keys>, dead_zone: (f32, f32)) -> Self {
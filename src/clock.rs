@@ -0,0 +1,86 @@
+//! The clock used to measure how long a button or axis has been pressed. By default it follows Bevy's
+//! [`Time`], but it can be paused, time-scaled or driven manually (e.g. for tests and replays).
+use bevy::{core::Time, prelude::Res, prelude::ResMut, utils::Duration};
+
+/// Where the [`InputClock`] takes its time from.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ClockSource {
+    /// The clock advances by the delta of Bevy's [`Time`] resource every frame.
+    Time,
+    /// The clock only advances when [`InputClock::advance`] or [`InputClock::set`] are called.
+    Manual,
+}
+
+/// A resource providing the current time for every press state, read by the input plugin once per tick.
+#[derive(PartialEq, Clone, Debug)]
+pub struct InputClock {
+    pub source: ClockSource,
+    /// The speed in which the clock advances; a scale of `0.5` makes every hold duration take twice as long.
+    pub scale: f32,
+    /// Whether the clock is paused or not. A paused clock doesn't advance at all.
+    pub paused: bool,
+    now: Duration,
+}
+
+impl Default for InputClock {
+    fn default() -> Self {
+        Self::new(ClockSource::Time)
+    }
+}
+
+impl InputClock {
+    /// Creates a clock starting at zero for the given source.
+    pub fn new(source: ClockSource) -> Self {
+        Self {
+            source,
+            scale: 1.,
+            paused: false,
+            now: Duration::ZERO,
+        }
+    }
+
+    /// Creates a clock that only advances manually.
+    pub fn manual() -> Self {
+        Self::new(ClockSource::Manual)
+    }
+
+    /// Returns the current time of this clock.
+    #[inline]
+    pub fn now(&self) -> Duration {
+        self.now
+    }
+
+    /// Advance the clock by the given delta, taking the scale and pause state into account.
+    pub fn advance(&mut self, delta: Duration) {
+        if !self.paused {
+            self.now += delta.mul_f32(self.scale.max(0.));
+        }
+    }
+
+    /// Set the current time of this clock.
+    pub fn set(&mut self, now: Duration) {
+        self.now = now;
+    }
+}
+
+/// Advance the input clock with Bevy's [`Time`] if it is the clock source.
+#[doc(hidden)]
+pub(crate) fn clock_system(mut clock: ResMut<InputClock>, time: Option<Res<Time>>) {
+    if let (ClockSource::Time, Some(time)) = (clock.source, time) {
+        clock.advance(time.delta());
+    }
+}
+
+// Test to check if pausing and scaling the clock is respected when advancing it.
+#[test]
+fn clock_pause_and_scale_test() {
+    let mut clock = InputClock::manual();
+    clock.advance(Duration::from_secs(1));
+    clock.paused = true;
+    clock.advance(Duration::from_secs(1));
+    assert_eq!(clock.now(), Duration::from_secs(1));
+    clock.paused = false;
+    clock.scale = 0.5;
+    clock.advance(Duration::from_secs(2));
+    assert_eq!(clock.now(), Duration::from_secs(2));
+}
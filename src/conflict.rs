@@ -0,0 +1,231 @@
+//! Detection of bindings sharing the same receivers, and the policies used to decide which action wins when
+//! the receivers of more than one binding are pressed at the same time (e.g. `S` and `Ctrl+S`).
+use crate::prelude::*;
+
+/// How the input view resolves conflicting bindings when their receivers are pressed.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub enum ConflictPolicy {
+    /// Every binding is triggered, regardless of conflicts.
+    #[default]
+    AllowAll,
+    /// Only the binding with the most receivers is triggered, so `Ctrl+S` doesn't also trigger `S`.
+    MostSpecific,
+    /// Only the binding registered first in the input view is triggered.
+    FirstRegistered,
+}
+
+/// The kind of conflict between two receiver combinations.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ConflictKind {
+    /// Both combinations have exactly the same receivers.
+    Duplicate,
+    /// All receivers of the first combination are part of the second one.
+    Subset,
+}
+
+/// A conflict between receiver combinations of two different actions.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct BindingConflict<Keys>
+where
+    Keys: BindingTypeView,
+{
+    pub action: Keys,
+    pub receivers: InputReceivers,
+    pub other: Keys,
+    pub other_receivers: InputReceivers,
+    pub kind: ConflictKind,
+}
+
+/// Returns whether all receivers of `a` are part of `b`.
+fn is_subset(a: &InputReceivers, b: &InputReceivers) -> bool {
+    a.0.iter().all(|rcv| b.0.contains(rcv))
+}
+
+/// Returns the receiver combinations of a binding, with every receiver of its 2D axis sources and every
+/// sequence step as a combination of its own.
+fn combinations<Keys>(binding: &ActionBinding<Keys>) -> Vec<InputReceivers>
+where
+    Keys: BindingTypeView,
+{
+    let mut output: Vec<InputReceivers> = Vec::new();
+    let sources = binding
+        .dual_axes
+        .iter()
+        .flat_map(|source| source.receivers())
+        .map(|rcv| InputReceivers(vec![rcv]));
+    let steps = binding
+        .sequences
+        .iter()
+        .flat_map(|seq| seq.steps.iter().cloned());
+    for rcvs in binding
+        .input_receivers
+        .iter()
+        .cloned()
+        .chain(sources)
+        .chain(steps)
+    {
+        if !rcvs.0.is_empty() && !output.contains(&rcvs) {
+            output.push(rcvs);
+        }
+    }
+    output
+}
+
+impl<Keys> InputView<Keys>
+where
+    Keys: BindingTypeView,
+{
+    /// Report every conflict between the bindings of this view. Duplicates are reported once per pair of
+    /// actions, while subsets are reported with the less specific combination first. Each receiver of a 2D axis
+    /// source and each sequence step is compared as a combination of its own.
+    pub fn conflicts(&self) -> Vec<BindingConflict<Keys>> {
+        let mut output = Vec::new();
+        let bindings: Vec<(Keys, Vec<InputReceivers>)> = self
+            .bindings
+            .values()
+            .map(|binding| (binding.key, combinations(binding)))
+            .collect();
+        for (index, (key, combos)) in bindings.iter().enumerate() {
+            for (other, other_combos) in bindings.iter() {
+                if key == other {
+                    continue;
+                }
+                for rcvs in combos.iter() {
+                    for other_rcvs in other_combos.iter() {
+                        if !is_subset(rcvs, other_rcvs) {
+                            continue;
+                        }
+                        let kind = if is_subset(other_rcvs, rcvs) {
+                            // Report duplicates only for the first binding of the pair.
+                            if bindings.iter().position(|(k, _)| k == other) < Some(index) {
+                                continue;
+                            }
+                            ConflictKind::Duplicate
+                        } else {
+                            ConflictKind::Subset
+                        };
+                        output.push(BindingConflict {
+                            action: *key,
+                            receivers: rcvs.clone(),
+                            other: *other,
+                            other_receivers: other_rcvs.clone(),
+                            kind,
+                        });
+                    }
+                }
+            }
+        }
+        output
+    }
+
+    /// Returns whether the given receiver combination of an action is suppressed by another binding according
+    /// to the conflict policy of this view. A combination of another binding is considered only when all of its
+    /// receivers are active. The buttons of 2D axis sources are checked as single receivers.
+    pub(crate) fn is_suppressed(
+        &self,
+        kind: &Keys,
+        rcvs: &InputReceivers,
+        active: impl Fn(&AxisState) -> bool,
+    ) -> bool {
+        if self.conflict_policy == ConflictPolicy::AllowAll {
+            return false;
+        }
+        let order = |key: &Keys| {
+            self.binding_order
+                .iter()
+                .position(|k| k == key)
+                .unwrap_or(usize::MAX)
+        };
+        let wins = |other: &Keys, other_rcvs: &InputReceivers| match self.conflict_policy {
+            ConflictPolicy::AllowAll => false,
+            ConflictPolicy::MostSpecific => {
+                is_subset(rcvs, other_rcvs) && !is_subset(other_rcvs, rcvs)
+            }
+            ConflictPolicy::FirstRegistered => {
+                (is_subset(rcvs, other_rcvs) || is_subset(other_rcvs, rcvs))
+                    && order(other) < order(kind)
+            }
+        };
+        self.bindings
+            .values()
+            .filter(|other| other.key != *kind)
+            .any(|other| {
+                other.input_receivers.iter().any(|other_rcvs| {
+                    !other_rcvs.0.is_empty()
+                        && wins(&other.key, other_rcvs)
+                        && other_rcvs.0.iter().all(|rcv| active(self.state(rcv)))
+                })
+            })
+    }
+}
+
+// Test to check if conflicts are reported, including 2D axis sources and sequence steps, and resolved according
+// to the policy.
+#[test]
+fn conflict_policy_test() {
+    use bevy::{prelude::KeyCode, utils::Duration};
+
+    #[derive(crate::BindingTypeView, PartialEq, Eq, Hash, Clone, Copy, Debug)]
+    enum Action {
+        Down,
+        Save,
+        Move,
+        Dash,
+    }
+    let (ctrl, s) = (
+        InputReceiver::KeyboardKey(KeyCode::LControl),
+        InputReceiver::KeyboardKey(KeyCode::S),
+    );
+    let pressed = PressState::Pressed {
+        started_pressing_instant: None,
+    };
+    let mut view = InputView::new();
+    view.add_binding(&mut ActionBinding::new_from_vec(
+        Action::Down,
+        vec![vec![s]],
+    ));
+    view.add_binding(&mut ActionBinding::new_from_vec(
+        Action::Save,
+        vec![vec![ctrl, s]],
+    ));
+    view.add_binding(ActionBinding::from(Action::Move).dual_axis(DualAxis::wasd()));
+    view.add_binding(ActionBinding::from(Action::Dash).sequence(Sequence::new(
+        vec![InputReceivers(vec![ctrl]), InputReceivers(vec![ctrl])],
+        Duration::from_millis(200),
+    )));
+    let conflicts = view.conflicts();
+    let subset = |action, rcvs: Vec<InputReceiver>| BindingConflict {
+        action,
+        receivers: InputReceivers(rcvs),
+        other: Action::Save,
+        other_receivers: InputReceivers(vec![ctrl, s]),
+        kind: ConflictKind::Subset,
+    };
+    assert_eq!(conflicts.len(), 4);
+    assert!(conflicts.contains(&subset(Action::Down, vec![s])));
+    assert!(conflicts.contains(&subset(Action::Move, vec![s])));
+    assert!(conflicts.contains(&subset(Action::Dash, vec![ctrl])));
+    assert!(conflicts.iter().any(|conflict| {
+        conflict.kind == ConflictKind::Duplicate
+            && [conflict.action, conflict.other].contains(&Action::Down)
+            && [conflict.action, conflict.other].contains(&Action::Move)
+    }));
+
+    view.set_key_receiver_state(ctrl, pressed);
+    view.set_key_receiver_state(s, pressed);
+    assert!(view.key(&Action::Down).pressed() && view.key(&Action::Save).pressed());
+    assert_eq!(view.axis_pair(&Action::Move).y, -1.);
+
+    view.conflict_policy = ConflictPolicy::MostSpecific;
+    assert!(view.key(&Action::Down).released() && view.key(&Action::Save).pressed());
+    assert!(!view.just_pressed(&Action::Down) && view.just_pressed(&Action::Save));
+    assert_eq!(view.axis_pair(&Action::Move).y, 0.);
+
+    view.conflict_policy = ConflictPolicy::FirstRegistered;
+    assert!(view.key(&Action::Down).pressed() && view.key(&Action::Save).released());
+
+    view.conflict_policy = ConflictPolicy::MostSpecific;
+    view.bindings.get_mut(&Action::Down).unwrap().toggle(true);
+    view.update_accessibility();
+    assert!(view.key(&Action::Down).released());
+}
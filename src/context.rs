@@ -0,0 +1,371 @@
+//! Input contexts, sets of bindings that can be pushed onto and popped from an [`InputView`] to switch between
+//! control schemes (e.g. gameplay, vehicle, menu and chat) without rebuilding the view.
+//!
+//! The bindings of the view when the first context is pushed act as the base context, below every other one.
+//! Contexts are ordered by priority and then by the order they were pushed, and an action defined by more than
+//! one context is taken from the topmost one. The receivers of a consuming context are removed from every
+//! context below it, so Escape in a menu doesn't also pause the game.
+use std::collections::{hash_map::Entry, HashMap, HashSet};
+
+use crate::prelude::*;
+
+/// The bindings of a view and their order, kept while contexts are pushed.
+pub(crate) type BaseBindings<Keys> = (HashMap<Keys, ActionBinding<Keys>>, Vec<Keys>);
+
+/// A named set of bindings.
+#[derive(PartialEq, Clone, Debug)]
+pub struct InputContext<Keys>
+where
+    Keys: BindingTypeView,
+{
+    pub name: String,
+    /// Contexts with a higher priority are above contexts with a lower priority, regardless of the order they
+    /// were pushed.
+    pub priority: i32,
+    /// Whether the receivers of this context are removed from the contexts below it.
+    pub consume: bool,
+    pub bindings: Vec<ActionBinding<Keys>>,
+}
+
+impl<Keys> InputContext<Keys>
+where
+    Keys: BindingTypeView,
+{
+    /// Creates an empty consuming context with the default priority.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            priority: 0,
+            consume: true,
+            bindings: Vec::new(),
+        }
+    }
+
+    /// Set the priority of this context.
+    pub fn priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Set whether the receivers of this context are removed from the contexts below it.
+    pub fn consume(mut self, consume: bool) -> Self {
+        self.consume = consume;
+        self
+    }
+
+    /// Add a binding to this context.
+    pub fn binding(mut self, binding: ActionBinding<Keys>) -> Self {
+        self.bindings.push(binding);
+        self
+    }
+
+    /// Returns every receiver used by the bindings of this context.
+    pub fn receivers(&self) -> HashSet<InputReceiver> {
+        self.bindings
+            .iter()
+            .flat_map(|binding| {
+                let combinations = binding
+                    .input_receivers
+                    .iter()
+                    .flat_map(|rcvs| rcvs.0.clone());
+                let dual_axes = binding.dual_axes.iter().flat_map(|dual| dual.receivers());
+                combinations.chain(dual_axes).collect::<Vec<_>>()
+            })
+            .collect()
+    }
+}
+
+/// Returns a copy of the binding without the receiver combinations and 2D axis sources using consumed receivers.
+fn without_consumed<Keys>(
+    binding: &ActionBinding<Keys>,
+    consumed: &HashSet<InputReceiver>,
+) -> ActionBinding<Keys>
+where
+    Keys: BindingTypeView,
+{
+    let mut binding = binding.clone();
+    binding
+        .input_receivers
+        .retain(|rcvs| rcvs.0.iter().all(|rcv| !consumed.contains(rcv)));
+    binding
+        .dual_axes
+        .retain(|dual| dual.receivers().iter().all(|rcv| !consumed.contains(rcv)));
+    binding
+}
+
+/// Carry the runtime state of the previous binding of the same action, that is, smoothed values and sequence
+/// progress, over to a rebuilt binding, as long as their configuration didn't change.
+fn keep_runtime_state<Keys>(binding: &mut ActionBinding<Keys>, previous: &ActionBinding<Keys>)
+where
+    Keys: BindingTypeView,
+{
+    if binding.processors == previous.processors {
+        binding.processors.clone_from(&previous.processors);
+    }
+    if binding.receiver_processors == previous.receiver_processors {
+        binding
+            .receiver_processors
+            .clone_from(&previous.receiver_processors);
+    }
+    for (sequence, previous) in binding.sequences.iter_mut().zip(previous.sequences.iter()) {
+        if sequence == previous {
+            sequence.clone_from(previous);
+        }
+    }
+}
+
+impl<Keys> InputView<Keys>
+where
+    Keys: BindingTypeView,
+{
+    /// Push a context above the contexts with the same or a lower priority.
+    pub fn push_context(&mut self, context: InputContext<Keys>) -> &mut Self {
+        if self.base_bindings.is_none() {
+            self.base_bindings = Some((self.bindings.clone(), self.binding_order.clone()));
+        }
+        self.contexts.push(context);
+        self.apply_contexts();
+        self
+    }
+
+    /// Remove the context pushed last, restoring the bindings below it.
+    pub fn pop_context(&mut self) -> Option<InputContext<Keys>> {
+        let context = self.contexts.pop()?;
+        self.apply_contexts();
+        Some(context)
+    }
+
+    /// Remove the context pushed last with the given name.
+    pub fn remove_context(&mut self, name: &str) -> Option<InputContext<Keys>> {
+        let index = self.contexts.iter().rposition(|ctx| ctx.name == name)?;
+        let context = self.contexts.remove(index);
+        self.apply_contexts();
+        Some(context)
+    }
+
+    /// Returns whether a context with the given name is pushed.
+    pub fn has_context(&self, name: &str) -> bool {
+        self.contexts.iter().any(|ctx| ctx.name == name)
+    }
+
+    /// Returns the topmost context, which receives input before every other one.
+    pub fn top_context(&self) -> Option<&InputContext<Keys>> {
+        self.ordered_contexts().into_iter().next()
+    }
+
+    /// Returns the pushed contexts from the topmost to the bottommost one.
+    fn ordered_contexts(&self) -> Vec<&InputContext<Keys>> {
+        let mut contexts: Vec<(usize, &InputContext<Keys>)> =
+            self.contexts.iter().enumerate().collect();
+        contexts.sort_by(|(a_index, a), (b_index, b)| {
+            b.priority.cmp(&a.priority).then(b_index.cmp(a_index))
+        });
+        contexts.into_iter().map(|(_, ctx)| ctx).collect()
+    }
+
+    /// Edit the bindings of this view and their order. While contexts are pushed, the base bindings below them
+    /// are edited instead, and the contexts are applied again.
+    pub(crate) fn edit_base_bindings<R>(
+        &mut self,
+        edit: impl FnOnce(&mut HashMap<Keys, ActionBinding<Keys>>, &mut Vec<Keys>) -> R,
+    ) -> R {
+        let result = match self.base_bindings.as_mut() {
+            Some((base, order)) => edit(base, order),
+            None => edit(&mut self.bindings, &mut self.binding_order),
+        };
+        self.apply_contexts();
+        result
+    }
+
+    /// Reset the default values of every receiver and apply the ones of the given bindings in order, so the
+    /// last binding of a shared receiver sets its default value.
+    fn apply_default_axis_values<'a>(
+        &mut self,
+        bindings: &mut HashMap<Keys, ActionBinding<Keys>>,
+        order: impl Iterator<Item = &'a Keys>,
+    ) {
+        for descriptor in self.descriptors.iter_mut() {
+            descriptor.default_axis_value = 0.;
+        }
+        for key in order {
+            if let Some(binding) = bindings.get_mut(key) {
+                binding.apply_default_axis_to_all_receivers(self);
+            }
+        }
+    }
+
+    /// Rebuild the bindings of this view from the base bindings and the pushed contexts, keeping the runtime
+    /// state of the current bindings.
+    pub(crate) fn apply_contexts(&mut self) {
+        let (mut base, base_order) = match &self.base_bindings {
+            Some(base) => base.clone(),
+            None => return,
+        };
+        if self.contexts.is_empty() {
+            self.apply_default_axis_values(&mut base, base_order.iter());
+            for binding in base.values_mut() {
+                if let Some(previous) = self.bindings.get(&binding.key) {
+                    keep_runtime_state(binding, previous);
+                }
+            }
+            self.bindings = base;
+            self.binding_order = base_order;
+            self.base_bindings = None;
+            return;
+        }
+
+        let mut bindings: HashMap<Keys, ActionBinding<Keys>> = HashMap::new();
+        let mut order: Vec<Keys> = Vec::new();
+        let mut consumed: HashSet<InputReceiver> = HashSet::new();
+        for context in self.ordered_contexts() {
+            for binding in context.bindings.iter() {
+                if let Entry::Vacant(entry) = bindings.entry(binding.key) {
+                    entry.insert(without_consumed(binding, &consumed));
+                    order.push(binding.key);
+                }
+            }
+            if context.consume {
+                consumed.extend(context.receivers());
+            }
+        }
+        let context_keys = order.len();
+        for key in base_order.iter() {
+            if let (Some(binding), false) = (base.get(key), bindings.contains_key(key)) {
+                bindings.insert(*key, without_consumed(binding, &consumed));
+                order.push(*key);
+            }
+        }
+
+        // The base bindings keep the order they were added in, and the contexts are applied from the bottommost
+        // to the topmost one, so the topmost binding of a shared receiver sets its default value.
+        let (context_order, base_order) = order.split_at(context_keys);
+        self.apply_default_axis_values(
+            &mut bindings,
+            base_order.iter().chain(context_order.iter().rev()),
+        );
+        for binding in bindings.values_mut() {
+            if let Some(previous) = self.bindings.get(&binding.key) {
+                keep_runtime_state(binding, previous);
+            }
+        }
+        self.bindings = bindings;
+        self.binding_order = order;
+    }
+}
+
+// Test to check if the topmost context consumes its receivers, popping it restores the bindings below and
+// changing the bindings while it is pushed changes the bindings below.
+#[test]
+fn input_context_test() {
+    use crate::harness::PRESSED;
+    use bevy::prelude::KeyCode;
+
+    #[derive(crate::BindingTypeView, PartialEq, Eq, Hash, Clone, Copy, Debug)]
+    enum Action {
+        Pause,
+        Jump,
+        Close,
+        Send,
+    }
+    let (escape, space, enter) = (
+        InputReceiver::KeyboardKey(KeyCode::Escape),
+        InputReceiver::KeyboardKey(KeyCode::Space),
+        InputReceiver::KeyboardKey(KeyCode::Return),
+    );
+    let mut view = InputView::new();
+    view.add_binding(&mut ActionBinding::new_from_vec(
+        Action::Pause,
+        vec![vec![escape]],
+    ));
+    view.add_binding(
+        ActionBinding::new_from_vec(Action::Jump, vec![vec![space]])
+            .processor(AxisProcessor::smoothing(0.5)),
+    );
+    view.set_key_receiver_state(escape, PRESSED);
+    view.set_key_receiver_state(space, PRESSED);
+
+    view.push_context(
+        InputContext::new("chat")
+            .priority(1)
+            .binding(ActionBinding::new_from_vec(Action::Send, vec![vec![enter]])),
+    );
+    view.push_context(
+        InputContext::new("menu").binding(ActionBinding::new_from_vec(
+            Action::Close,
+            vec![vec![escape]],
+        )),
+    );
+    assert_eq!(view.top_context().unwrap().name, "chat");
+    assert!(view.key(&Action::Close).pressed());
+    assert!(view.key(&Action::Pause).released());
+    assert!(view.key(&Action::Jump).pressed());
+
+    assert_eq!(view.remove_context("chat").unwrap().name, "chat");
+    assert!(!view.bindings.contains_key(&Action::Send));
+    view.pop_context();
+    assert!(view.key(&Action::Pause).pressed());
+    assert!(!view.bindings.contains_key(&Action::Close));
+    assert!(!view.has_context("menu"));
+
+    // Rebinding while a context is pushed changes the base binding, which is kept when popping the context,
+    // along with the smoothed value computed in the meantime.
+    view.push_context(InputContext::new("menu"));
+    view.bindings
+        .get_mut(&Action::Jump)
+        .unwrap()
+        .processors
+        .step(1.);
+    let rebind = RebindEvent {
+        entity: bevy::prelude::Entity::from_raw(0),
+        action: Action::Pause,
+        replacing: None,
+        result: RebindResult::Captured(InputReceivers(vec![enter])),
+    };
+    assert!(rebind.apply(&mut view));
+    view.clear_from_specific_source(InputSource::Mouse);
+    view.pop_context();
+    let pause = &view.bindings[&Action::Pause].input_receivers;
+    assert!(pause.len() == 1 && pause.contains(&InputReceivers(vec![enter])));
+    assert_eq!(view.bindings[&Action::Jump].processors.process(0.), 0.5);
+}
+
+// Test to check if the topmost binding of a shared receiver sets its default value, and if popping the context
+// restores the default value of the base binding.
+#[test]
+fn input_context_default_axis_test() {
+    use bevy::prelude::KeyCode;
+
+    #[derive(crate::BindingTypeView, PartialEq, Eq, Hash, Clone, Copy, Debug)]
+    enum Action {
+        Vertical,
+        Brake,
+        Reverse,
+    }
+    let s = InputReceiver::KeyboardKey(KeyCode::S);
+    let mut view = InputView::new();
+    view.add_binding(
+        ActionBinding::new_from_vec(Action::Vertical, vec![vec![s]]).default_axis_value(s, -1.),
+    );
+    let default_of = |view: &InputView<Action>| view.descriptor(&s).unwrap().default_axis_value;
+
+    view.push_context(
+        InputContext::new("vehicle").consume(false).binding(
+            ActionBinding::new_from_vec(Action::Brake, vec![vec![s]])
+                .default_axis_value(s, 0.5)
+                .clone(),
+        ),
+    );
+    assert_eq!(default_of(&view), 0.5);
+    view.push_context(
+        InputContext::new("reverse").consume(false).binding(
+            ActionBinding::new_from_vec(Action::Reverse, vec![vec![s]])
+                .default_axis_value(s, 0.25)
+                .clone(),
+        ),
+    );
+    assert_eq!(default_of(&view), 0.25);
+    view.pop_context();
+    assert_eq!(default_of(&view), 0.5);
+    view.pop_context();
+    assert_eq!(default_of(&view), -1.);
+}
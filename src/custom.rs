@@ -0,0 +1,232 @@
+//! Extension point for input sources that aren't built into EZInput, such as network-fed controllers, AI agents
+//! or MIDI devices. A custom source is a marker component implementing [`CustomInputSource`], and its receivers
+//! are stored as [`InputReceiver::Custom`], so they can be bound, cleared and serialized like any other receiver.
+//!
+//! Register the handling system of a source with [`CustomInputPlugin`], next to the [`EZInputPlugin`] of the
+//! same keys. To also feed the global view, insert a marker of the source as a [`GlobalCustomInput`] resource.
+use std::marker::PhantomData;
+
+use bevy::{
+    input::InputSystem,
+    prelude::{
+        App, Component, CoreStage, EventReader, ParallelSystemDescriptorCoercion, Plugin, Query,
+        ResMut, SystemLabel, Without,
+    },
+};
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+
+#[derive(SystemLabel, Clone, Hash, Debug, PartialEq, Eq)]
+pub struct CustomInputHandlingSystem;
+
+/// The identifier of a custom input source. It is stored in serialized bindings, so it must be unique among the
+/// custom sources of an application and shouldn't change between versions.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug, Deserialize, Serialize)]
+#[serde(transparent)]
+pub struct CustomSource(pub u16);
+
+/// A marker component handling input from a custom source for an entity.
+pub trait CustomInputSource: Component {
+    /// The identifier of this source.
+    const SOURCE: CustomSource;
+    /// The receivers of this source, converted to the identifiers stored in [`InputReceiver::Custom`].
+    type Receiver: Copy + Into<u32>;
+    /// The event read by the handling system of this source.
+    type Event: Send + Sync + 'static;
+
+    /// Handle an event of this source for the given view.
+    fn handle_event<Keys>(&mut self, view: &mut InputView<Keys>, event: &Self::Event)
+    where
+        Keys: BindingTypeView;
+
+    /// Called after all events of the current tick are handled, e.g. to release receivers that didn't change.
+    fn tick<Keys>(&mut self, _view: &mut InputView<Keys>)
+    where
+        Keys: BindingTypeView,
+    {
+    }
+
+    /// Returns the event pressing, releasing or moving the given receiver of this source, used by the
+    /// [`InputSimulator`] once the source is registered with [`InputSimulator::simulate_source`]. By default, the
+    /// receivers of this source can't be simulated.
+    fn simulated_event(_receiver: u32, _value: f32, _pressed: bool) -> Option<Self::Event> {
+        None
+    }
+
+    /// Returns the input receiver for a receiver of this source.
+    fn receiver(receiver: Self::Receiver) -> InputReceiver {
+        InputReceiver::Custom(Self::SOURCE, receiver.into())
+    }
+
+    /// Set the button state for a receiver of this source and set the last input source to this source.
+    fn set_receiver_state<Keys>(
+        &mut self,
+        view: &mut InputView<Keys>,
+        receiver: Self::Receiver,
+        state: PressState,
+    ) where
+        Keys: BindingTypeView,
+    {
+        view.last_input_source = Some(InputSource::Custom(Self::SOURCE));
+        view.set_key_receiver_state(Self::receiver(receiver), state);
+    }
+
+    /// Set the axis state for a receiver of this source and set the last input source to this source.
+    fn set_receiver_value<Keys>(
+        &mut self,
+        view: &mut InputView<Keys>,
+        receiver: Self::Receiver,
+        value: f32,
+        state: PressState,
+    ) where
+        Keys: BindingTypeView,
+    {
+        view.last_input_source = Some(InputSource::Custom(Self::SOURCE));
+        view.set_axis_value(Self::receiver(receiver), value, state);
+    }
+}
+
+/// The marker of a custom source handling input for the global view of every key type.
+pub struct GlobalCustomInput<Source>(pub Source)
+where
+    Source: CustomInputSource;
+
+/// A [`Plugin`] registering the event and handling system of a custom input source for the given keys.
+pub struct CustomInputPlugin<Keys, Source>
+where
+    Keys: BindingTypeView,
+    Source: CustomInputSource,
+{
+    phantom: PhantomData<(Keys, Source)>,
+}
+
+impl<Keys, Source> Default for CustomInputPlugin<Keys, Source>
+where
+    Keys: BindingTypeView,
+    Source: CustomInputSource,
+{
+    fn default() -> Self {
+        Self {
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<Keys, Source> Plugin for CustomInputPlugin<Keys, Source>
+where
+    Keys: BindingTypeView,
+    Source: CustomInputSource,
+{
+    fn build(&self, app: &mut App) {
+        app.add_event::<Source::Event>().add_system_to_stage(
+            CoreStage::PreUpdate,
+            custom_input_system::<Keys, Source>
+                .label(CustomInputHandlingSystem)
+                .after(InputHandlingTickSystem)
+                .after(InputSystem)
+                .before(RebindingSystem)
+                .before(SequenceSystem)
+                .before(AxisProcessingSystem),
+        );
+    }
+}
+
+/// Input system responsible for handling the events of a custom source for every entity with its marker, and for
+/// the global view when a [`GlobalCustomInput`] of the source exists. Entities being replayed are skipped.
+pub(crate) fn custom_input_system<Keys, Source>(
+    mut query: Query<(&mut InputView<Keys>, &mut Source), Without<InputReplay>>,
+    global: Option<ResMut<InputView<Keys>>>,
+    global_source: Option<ResMut<GlobalCustomInput<Source>>>,
+    mut rd: EventReader<Source::Event>,
+) where
+    Keys: BindingTypeView,
+    Source: CustomInputSource,
+{
+    let events: Vec<&Source::Event> = rd.iter().collect();
+    let views = query
+        .iter_mut()
+        .map(|(view, source)| (view.into_inner(), source.into_inner()));
+    let global = global
+        .zip(global_source)
+        .map(|(view, source)| (view.into_inner(), &mut source.into_inner().0));
+    for (view, source) in views.chain(global) {
+        for ev in events.iter() {
+            source.handle_event(view, ev);
+        }
+        source.tick(view);
+    }
+}
+
+// Test to check if the receivers of a custom source are handled, serialized and cleared like built-in ones, and
+// feed the global view.
+#[test]
+fn custom_input_source_test() {
+    use bevy::{
+        ecs::event::Events,
+        prelude::{Stage, SystemStage, World},
+    };
+
+    #[derive(
+        crate::BindingTypeView, PartialEq, Eq, Hash, Clone, Copy, Debug, Deserialize, Serialize,
+    )]
+    enum Action {
+        Play,
+    }
+    struct NoteEvent(u8, bool);
+    #[derive(Component)]
+    struct MidiMarker;
+    impl CustomInputSource for MidiMarker {
+        const SOURCE: CustomSource = CustomSource(7);
+        type Receiver = u8;
+        type Event = NoteEvent;
+
+        fn handle_event<Keys>(&mut self, view: &mut InputView<Keys>, event: &NoteEvent)
+        where
+            Keys: BindingTypeView,
+        {
+            let state = if event.1 {
+                PressState::Pressed {
+                    started_pressing_instant: None,
+                }
+            } else {
+                PressState::Released
+            };
+            self.set_receiver_state(view, event.0, state);
+        }
+    }
+
+    let note = MidiMarker::receiver(60);
+    let mut view = InputView::new();
+    view.add_binding(&mut ActionBinding::new_from_vec(
+        Action::Play,
+        vec![vec![note]],
+    ));
+    let saved = BindingProfile::from_view(&view)
+        .to_string(ProfileFormat::Ron)
+        .unwrap();
+    assert!(saved.contains("Custom(7, 60)"));
+
+    let mut world = World::new();
+    world.insert_resource(Events::<NoteEvent>::default());
+    world.insert_resource(view.clone());
+    world.insert_resource(GlobalCustomInput(MidiMarker));
+    let entity = world.spawn().insert(view).insert(MidiMarker).id();
+    world
+        .resource_mut::<Events<NoteEvent>>()
+        .send(NoteEvent(60, true));
+    SystemStage::single(custom_input_system::<Action, MidiMarker>).run(&mut world);
+
+    assert!(world
+        .resource::<InputView<Action>>()
+        .key(&Action::Play)
+        .pressed());
+    let mut view = world.get_mut::<InputView<Action>>(entity).unwrap();
+    assert!(view.key(&Action::Play).pressed());
+    assert_eq!(
+        view.last_input_source,
+        Some(InputSource::Custom(CustomSource(7)))
+    );
+    view.clear_from_specific_source(InputSource::Custom(MidiMarker::SOURCE));
+    assert!(view.bindings[&Action::Play].input_receivers.is_empty());
+}
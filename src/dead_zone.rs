@@ -0,0 +1,168 @@
+//! Dead zone processing for gamepad sticks and triggers. Values inside the inner dead zone are zeroed, values
+//! beyond the outer dead zone are saturated and, except for [`DeadZone::Radial`], the remaining range is
+//! rescaled to `0..1` so there is no jump when leaving the dead zone.
+use bevy::math::Vec2;
+use serde::{Deserialize, Serialize};
+
+/// The shape of a dead zone applied to a stick or trigger.
+#[derive(PartialEq, Clone, Copy, Debug, Deserialize, Serialize, Default)]
+pub enum DeadZone {
+    /// The value is kept as is.
+    #[default]
+    None,
+    /// Each axis is processed independently with its own inner dead zone. Good for digital-like movement,
+    /// but snaps diagonals to the axes near the center.
+    Axial { inner: Vec2, outer: f32 },
+    /// The stick is zeroed while its magnitude is inside the inner dead zone, and kept as is otherwise.
+    Radial { inner: f32, outer: f32 },
+    /// Like [`DeadZone::Radial`], but the magnitude between the inner and outer dead zones is rescaled to
+    /// `0..1`, keeping the stick direction.
+    ScaledRadial { inner: f32, outer: f32 },
+}
+
+/// Rescale a magnitude from the `inner..outer` range to `0..1`. A negative or NaN inner dead zone is treated as
+/// no dead zone, and an empty range saturates every magnitude beyond the inner dead zone.
+#[inline]
+fn rescale(magnitude: f32, inner: f32, outer: f32) -> f32 {
+    let inner = inner.max(0.);
+    if magnitude < inner {
+        0.
+    } else if outer <= inner {
+        1.
+    } else {
+        ((magnitude - inner) / (outer - inner)).min(1.)
+    }
+}
+
+/// Clamp an inner dead zone to the `0..1` range, so the remaining range isn't empty. NaN is clamped to zero.
+#[inline]
+fn clamp_inner(inner: f32) -> f32 {
+    if inner.is_nan() {
+        0.
+    } else {
+        inner.clamp(0., 1. - f32::EPSILON)
+    }
+}
+
+impl DeadZone {
+    /// Creates an axial dead zone with the same inner dead zone for both axes.
+    /// The inner dead zone is clamped to the `0..1` range.
+    pub fn axial(inner: f32) -> Self {
+        let inner = clamp_inner(inner);
+        DeadZone::Axial {
+            inner: Vec2::splat(inner),
+            outer: 1.,
+        }
+    }
+
+    /// Creates a radial dead zone.
+    /// The inner dead zone is clamped to the `0..1` range.
+    pub fn radial(inner: f32) -> Self {
+        let inner = clamp_inner(inner);
+        DeadZone::Radial { inner, outer: 1. }
+    }
+
+    /// Creates a scaled radial dead zone.
+    /// The inner dead zone is clamped to the `0..1` range.
+    pub fn scaled_radial(inner: f32) -> Self {
+        let inner = clamp_inner(inner);
+        DeadZone::ScaledRadial { inner, outer: 1. }
+    }
+
+    /// Apply this dead zone to a stick.
+    pub fn apply(&self, value: Vec2) -> Vec2 {
+        let magnitude = value.length();
+        // A centred stick has no direction to keep.
+        if magnitude == 0. {
+            return Vec2::ZERO;
+        }
+        match *self {
+            DeadZone::None => value,
+            DeadZone::Axial { inner, outer } => Vec2::new(
+                value.x.signum() * rescale(value.x.abs(), inner.x, outer),
+                value.y.signum() * rescale(value.y.abs(), inner.y, outer),
+            ),
+            DeadZone::Radial { inner, outer } => {
+                if magnitude < inner {
+                    Vec2::ZERO
+                } else if magnitude >= outer {
+                    value / magnitude
+                } else {
+                    value
+                }
+            }
+            DeadZone::ScaledRadial { inner, outer } => {
+                if magnitude < inner {
+                    Vec2::ZERO
+                } else {
+                    value / magnitude * rescale(magnitude, inner, outer)
+                }
+            }
+        }
+    }
+
+    /// Apply this dead zone to a single axis, such as a trigger, using its absolute value as the magnitude.
+    pub fn apply_axis(&self, value: f32) -> f32 {
+        match *self {
+            DeadZone::None => value,
+            DeadZone::Axial { inner, outer } => {
+                value.signum() * rescale(value.abs(), inner.x, outer)
+            }
+            DeadZone::Radial { inner, outer } => {
+                if value.abs() < inner {
+                    0.
+                } else if value.abs() >= outer {
+                    value.signum()
+                } else {
+                    value
+                }
+            }
+            DeadZone::ScaledRadial { inner, outer } => {
+                value.signum() * rescale(value.abs(), inner, outer)
+            }
+        }
+    }
+}
+
+// Test to check if the dead zones zero the center, rescale the remaining range and saturate the edge, without
+// dividing by zero for a centred stick.
+#[test]
+fn dead_zone_test() {
+    let scaled = DeadZone::ScaledRadial {
+        inner: 0.2,
+        outer: 0.9,
+    };
+    assert_eq!(scaled.apply(Vec2::new(0.1, 0.1)), Vec2::ZERO);
+    assert!(scaled.apply(Vec2::new(0.21, 0.)).x < 0.02);
+    assert!((scaled.apply(Vec2::new(0., -0.95)).y + 1.).abs() < f32::EPSILON);
+
+    let axial = DeadZone::axial(0.25);
+    assert_eq!(axial.apply(Vec2::new(0.2, 0.625)), Vec2::new(0., 0.5));
+
+    let radial = DeadZone::radial(0.25);
+    assert_eq!(radial.apply(Vec2::new(0.2, 0.)), Vec2::ZERO);
+    assert_eq!(radial.apply(Vec2::new(0.2, 0.2)), Vec2::new(0.2, 0.2));
+    assert_eq!(DeadZone::scaled_radial(0.5).apply_axis(0.75), 0.5);
+
+    let centred = [
+        DeadZone::scaled_radial(0.),
+        DeadZone::Radial {
+            inner: 0.,
+            outer: 0.,
+        },
+    ];
+    for dead_zone in centred {
+        assert_eq!(dead_zone.apply(Vec2::ZERO), Vec2::ZERO);
+    }
+
+    // Degenerate dead zones, e.g. from a settings slider reaching its end, don't panic nor leave drift.
+    assert_eq!(DeadZone::scaled_radial(1.).apply_axis(1.), 1.);
+    assert_eq!(DeadZone::scaled_radial(1.).apply_axis(0.5), 0.);
+    assert_eq!(DeadZone::axial(f32::NAN).apply_axis(0.5), 0.5);
+    let negative = DeadZone::Axial {
+        inner: Vec2::splat(-1.),
+        outer: 1.,
+    };
+    assert_eq!(negative.apply_axis(0.), 0.);
+    assert_eq!(negative.apply(Vec2::new(0.5, 0.)), Vec2::new(0.5, 0.));
+}
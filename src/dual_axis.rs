@@ -0,0 +1,199 @@
+//! Composite 2D axis bindings, combining four buttons (e.g. WASD), two axes (e.g. a stick) or the mouse delta
+//! into a single [`Vec2`] value for an action.
+use bevy::{
+    math::Vec2,
+    prelude::{GamepadAxisType, KeyCode},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+
+/// A source of a 2D axis value.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug, Deserialize, Serialize)]
+pub enum DualAxis {
+    /// Four buttons, each one moving the value by one in its direction while pressed.
+    Buttons {
+        up: InputReceiver,
+        down: InputReceiver,
+        left: InputReceiver,
+        right: InputReceiver,
+    },
+    /// Two axes, one for each direction.
+    Axes { x: InputReceiver, y: InputReceiver },
+}
+
+impl DualAxis {
+    /// The W, A, S and D keys.
+    pub fn wasd() -> Self {
+        DualAxis::Buttons {
+            up: KeyCode::W.into(),
+            down: KeyCode::S.into(),
+            left: KeyCode::A.into(),
+            right: KeyCode::D.into(),
+        }
+    }
+
+    /// The arrow keys.
+    pub fn arrows() -> Self {
+        DualAxis::Buttons {
+            up: KeyCode::Up.into(),
+            down: KeyCode::Down.into(),
+            left: KeyCode::Left.into(),
+            right: KeyCode::Right.into(),
+        }
+    }
+
+    /// The left stick of a gamepad.
+    pub fn left_stick() -> Self {
+        DualAxis::Axes {
+            x: GamepadAxisType::LeftStickX.into(),
+            y: GamepadAxisType::LeftStickY.into(),
+        }
+    }
+
+    /// The right stick of a gamepad.
+    pub fn right_stick() -> Self {
+        DualAxis::Axes {
+            x: GamepadAxisType::RightStickX.into(),
+            y: GamepadAxisType::RightStickY.into(),
+        }
+    }
+
+    /// The movement of the mouse in the current tick.
+    pub fn mouse_delta() -> Self {
+        DualAxis::Axes {
+            x: InputReceiver::MouseAxisDelta(MouseAxisType::X),
+            y: InputReceiver::MouseAxisDelta(MouseAxisType::Y),
+        }
+    }
+
+    /// The virtual joystick of a touch screen.
+    pub fn touch_joystick() -> Self {
+        DualAxis::Axes {
+            x: InputReceiver::TouchJoystick(TouchAxisType::X),
+            y: InputReceiver::TouchJoystick(TouchAxisType::Y),
+        }
+    }
+
+    /// Returns all receivers used by this source.
+    pub fn receivers(&self) -> Vec<InputReceiver> {
+        match *self {
+            DualAxis::Buttons {
+                up,
+                down,
+                left,
+                right,
+            } => vec![up, down, left, right],
+            DualAxis::Axes { x, y } => vec![x, y],
+        }
+    }
+
+    /// Returns the current value of this source for the given view, processed by the receiver pipelines of
+    /// the given binding. Buttons suppressed by the conflict policy of the view count as released.
+    pub fn value<Keys>(&self, view: &InputView<Keys>, binding: &ActionBinding<Keys>) -> Vec2
+    where
+        Keys: BindingTypeView,
+    {
+        let button = |rcv: &InputReceiver| {
+            let active = |state: &AxisState| state.press.pressed();
+            if view.state(rcv).press.pressed()
+                && !view.is_suppressed(&binding.key, &InputReceivers(vec![*rcv]), active)
+            {
+                binding.process_receiver(rcv, 1.)
+            } else {
+                0.
+            }
+        };
+        let axis = |rcv: &InputReceiver| {
+            let state = view.state(rcv);
+            if state.press.pressed() {
+                binding.process_receiver(rcv, state.value)
+            } else {
+                0.
+            }
+        };
+        match self {
+            DualAxis::Buttons {
+                up,
+                down,
+                left,
+                right,
+            } => Vec2::new(button(right) - button(left), button(up) - button(down)),
+            DualAxis::Axes { x, y } => Vec2::new(axis(x), axis(y)),
+        }
+    }
+}
+
+/// How the value of a 2D axis binding is limited.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug, Deserialize, Serialize, Default)]
+pub enum AxisPairNormalization {
+    /// The value is kept as is, which is useful for mouse movement.
+    #[default]
+    None,
+    /// The value is limited to the unit circle, so diagonals aren't faster than straight directions.
+    Circle,
+    /// Each direction is limited to the -1..1 range.
+    Square,
+}
+
+impl AxisPairNormalization {
+    /// Apply this normalization to a value.
+    pub fn apply(&self, value: Vec2) -> Vec2 {
+        match self {
+            AxisPairNormalization::None => value,
+            AxisPairNormalization::Circle => value.clamp_length_max(1.),
+            AxisPairNormalization::Square => value.clamp(Vec2::splat(-1.), Vec2::splat(1.)),
+        }
+    }
+}
+
+impl<Keys> InputView<Keys>
+where
+    Keys: BindingTypeView,
+{
+    /// Return the 2D axis value for the given key, combining all of its [`DualAxis`] sources and applying the
+    /// normalization of the binding. Only the receiver pipelines of the binding are applied, so processors like
+    /// inversion can be set for a single direction. A view with an applied snapshot returns the value of the
+    /// snapshot instead.
+    pub fn axis_pair(&self, kind: &Keys) -> Vec2 {
+        if let Some(pair) = self.snapshot_pair(kind) {
+            return pair;
+        }
+        self.bindings.get(kind).map_or(Vec2::ZERO, |binding| {
+            let value = binding
+                .dual_axes
+                .iter()
+                .fold(Vec2::ZERO, |acc, source| acc + source.value(self, binding));
+            binding.axis_pair_normalization.apply(value)
+        })
+    }
+}
+
+// Test to check if the four buttons and stick values are combined and normalized.
+#[test]
+fn axis_pair_test() {
+    #[derive(crate::BindingTypeView, PartialEq, Eq, Hash, Clone, Copy, Debug)]
+    enum Action {
+        Move,
+    }
+    let pressed = PressState::Pressed {
+        started_pressing_instant: None,
+    };
+    let mut view = InputView::new();
+    view.add_binding(
+        ActionBinding::from(Action::Move)
+            .dual_axis(DualAxis::wasd())
+            .dual_axis(DualAxis::left_stick())
+            .axis_pair_normalization(AxisPairNormalization::Circle),
+    );
+    view.set_key_receiver_state(KeyCode::W.into(), pressed);
+    view.set_key_receiver_state(KeyCode::D.into(), pressed);
+    let value = view.axis_pair(&Action::Move);
+    assert!((value.length() - 1.).abs() < f32::EPSILON);
+    assert!(value.x > 0. && (value.x - value.y).abs() < f32::EPSILON);
+
+    view.set_key_receiver_state(KeyCode::W.into(), PressState::Released);
+    view.set_key_receiver_state(KeyCode::D.into(), PressState::Released);
+    view.set_axis_value(GamepadAxisType::LeftStickX.into(), -0.5, pressed);
+    assert_eq!(view.axis_pair(&Action::Move), Vec2::new(-0.5, 0.));
+}
@@ -0,0 +1,130 @@
+//! Events emitted by the input plugin whenever the state of an action changes, so systems can react to input
+//! with an [`EventReader`] instead of polling the [`InputView`] every frame.
+use bevy::{
+    prelude::{Entity, EventWriter, Local, Query, SystemLabel},
+    utils::{Duration, HashMap},
+};
+
+use crate::prelude::*;
+
+#[derive(SystemLabel, Clone, Hash, Debug, PartialEq, Eq)]
+pub struct ActionEventSystem;
+
+/// The kind of change that happened to an action.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum ActionEventKind {
+    /// The action was pressed in the current tick.
+    Pressed,
+    /// The action was released in the current tick.
+    Released,
+    /// The action is still pressed, along with the elapsed time since it was pressed.
+    Held(Duration),
+    /// The axis value of the action has changed, along with the new value.
+    AxisChanged(f32),
+    /// An interaction of the action was triggered in the current tick.
+    Interaction(InputInteraction),
+}
+
+/// An event emitted for every change of an action for a specific entity.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct ActionEvent<Keys>
+where
+    Keys: BindingTypeView,
+{
+    pub entity: Entity,
+    pub action: Keys,
+    pub kind: ActionEventKind,
+}
+
+/// Compare every action with its state in the previous tick and emit the matching events. An action pressed and
+/// released within the same tick emits both events.
+pub(crate) fn action_event_system<Keys>(
+    query: Query<(Entity, &InputView<Keys>)>,
+    mut writer: EventWriter<ActionEvent<Keys>>,
+    mut last_values: Local<HashMap<(Entity, Keys), f32>>,
+) where
+    Keys: BindingTypeView,
+{
+    for (entity, view) in query.iter() {
+        for action in view.bindings.keys().copied() {
+            let mut send = |kind| {
+                writer.send(ActionEvent {
+                    entity,
+                    action,
+                    kind,
+                })
+            };
+            if view.just_pressed(&action) {
+                send(ActionEventKind::Pressed);
+            } else if view.pressed_this_frame(&action) && view.key(&action).released() {
+                // Tapped within a single tick.
+                send(ActionEventKind::Pressed);
+                send(ActionEventKind::Released);
+            } else if view.just_released(&action) {
+                send(ActionEventKind::Released);
+            } else if let Some(elapsed) = view.elapsed(&action) {
+                send(ActionEventKind::Held(elapsed));
+            }
+
+            for interaction in view.triggered(&action) {
+                send(ActionEventKind::Interaction(*interaction));
+            }
+
+            let value = view.axis(&action).first().map_or(0., |state| state.value);
+            let last_value = last_values.insert((entity, action), value).unwrap_or(0.);
+            if value != last_value {
+                send(ActionEventKind::AxisChanged(value));
+            }
+        }
+    }
+    // Forget the values of despawned entities and removed bindings.
+    last_values.retain(|(entity, action), _| {
+        query
+            .get(*entity)
+            .is_ok_and(|(_, view)| view.bindings.contains_key(action))
+    });
+}
+
+// Test to check if press, hold, axis and release events are emitted in order.
+#[test]
+fn action_event_test() {
+    use crate::harness::{ViewHarness, PRESSED};
+    use bevy::{ecs::event::Events, prelude::KeyCode};
+
+    #[derive(crate::BindingTypeView, PartialEq, Eq, Hash, Clone, Copy, Debug)]
+    enum Action {
+        Jump,
+    }
+    let space = InputReceiver::KeyboardKey(KeyCode::Space);
+    let mut view = InputView::new();
+    view.add_binding(
+        ActionBinding::new_from_vec(Action::Jump, vec![vec![space]]).default_axis_value(space, 1.),
+    );
+
+    let mut harness = ViewHarness::new(view, action_event_system::<Action>);
+    harness
+        .world
+        .insert_resource(Events::<ActionEvent<Action>>::default());
+    let mut tick = |states: &[PressState]| {
+        let changes: Vec<_> = states.iter().map(|state| (space, *state)).collect();
+        harness.tick(0, &changes);
+        let mut events = harness.world.resource_mut::<Events<ActionEvent<Action>>>();
+        let kinds: Vec<_> = events.drain().map(|ev| ev.kind).collect();
+        kinds
+    };
+
+    assert_eq!(
+        tick(&[PRESSED]),
+        vec![ActionEventKind::Pressed, ActionEventKind::AxisChanged(1.)]
+    );
+    assert_eq!(tick(&[]), vec![ActionEventKind::Held(Duration::ZERO)]);
+    assert_eq!(
+        tick(&[PressState::Released]),
+        vec![ActionEventKind::Released, ActionEventKind::AxisChanged(0.)]
+    );
+
+    assert_eq!(
+        tick(&[PRESSED, PressState::Released]),
+        vec![ActionEventKind::Pressed, ActionEventKind::Released]
+    );
+}
@@ -1,5 +1,5 @@
 //! Full gamepad support for EZInput.
-use bevy::prelude::*;
+use bevy::{prelude::*, utils::HashMap};
 
 use crate::prelude::*;
 
@@ -10,7 +10,23 @@ pub struct GamepadInputHandlingSystem;
 #[derive(PartialEq, Debug, Component, Clone)]
 pub struct GamepadMarker {
     pub gamepad: Gamepad,
-    pub dead_zone: Vec2,
+    /// Whether input from every gamepad is accepted, e.g. for menus. A button stays pressed while any gamepad
+    /// holds it, and a stick follows the gamepad that moved it last.
+    pub any_gamepad: bool,
+    /// The dead zone applied to both sticks.
+    pub dead_zone: DeadZone,
+    /// The dead zone applied to trigger axes and buttons.
+    pub trigger_dead_zone: DeadZone,
+    /// The minimum value, after the dead zone, for a stick axis to be considered pressed.
+    pub stick_press_threshold: f32,
+    /// The minimum value, after the dead zone, for a trigger to be considered pressed.
+    pub trigger_press_threshold: f32,
+    /// The minimum value for any other button or axis to be considered pressed.
+    pub button_press_threshold: f32,
+    /// The raw values of the left and right sticks of each gamepad, before applying the dead zone.
+    pub raw_sticks: HashMap<Gamepad, (Vec2, Vec2)>,
+    /// The raw button values of each gamepad, tracked when accepting any gamepad.
+    pub raw_buttons: HashMap<(Gamepad, GamepadButtonType), f32>,
 }
 
 impl Default for GamepadMarker {
@@ -23,13 +39,69 @@ impl GamepadMarker {
     pub fn with_id(id: usize) -> Self {
         Self {
             gamepad: Gamepad(id),
-            dead_zone: Vec2::ZERO,
+            any_gamepad: false,
+            dead_zone: DeadZone::None,
+            trigger_dead_zone: DeadZone::None,
+            stick_press_threshold: 0.1,
+            trigger_press_threshold: 0.1,
+            button_press_threshold: 0.1,
+            raw_sticks: HashMap::default(),
+            raw_buttons: HashMap::default(),
         }
     }
+
+    /// Creates a marker with an axial dead zone for each stick axis.
     pub fn with_dead_zone(id: usize, dead_zone: (f32, f32)) -> Self {
         Self {
-            gamepad: Gamepad(id),
-            dead_zone: Vec2::new(dead_zone.0, dead_zone.1),
+            dead_zone: DeadZone::Axial {
+                inner: Vec2::new(dead_zone.0, dead_zone.1),
+                outer: 1.,
+            },
+            ..Self::with_id(id)
+        }
+    }
+
+    /// Creates a marker accepting input from every gamepad.
+    pub fn any() -> Self {
+        Self {
+            any_gamepad: true,
+            ..Self::default()
+        }
+    }
+
+    /// Returns whether this marker accepts input from the given gamepad.
+    pub fn accepts(&self, gamepad: Gamepad) -> bool {
+        self.any_gamepad || self.gamepad == gamepad
+    }
+
+    /// Set the dead zone applied to both sticks.
+    pub fn stick_dead_zone(mut self, dead_zone: DeadZone) -> Self {
+        self.dead_zone = dead_zone;
+        self
+    }
+
+    /// Set the dead zone applied to triggers.
+    pub fn trigger_dead_zone(mut self, dead_zone: DeadZone) -> Self {
+        self.trigger_dead_zone = dead_zone;
+        self
+    }
+
+    /// Set the press thresholds for sticks and triggers.
+    pub fn press_thresholds(mut self, stick: f32, trigger: f32) -> Self {
+        self.stick_press_threshold = stick;
+        self.trigger_press_threshold = trigger;
+        self
+    }
+}
+
+/// Returns the press state for a processed value and a threshold.
+#[inline]
+fn press_state(value: f32, threshold: f32) -> PressState {
+    if value.abs() <= threshold {
+        PressState::Released
+    } else {
+        PressState::Pressed {
+            started_pressing_instant: None,
         }
     }
 }
@@ -62,63 +134,320 @@ impl GamepadMarker {
         view.last_input_source = Some(InputSource::Gamepad);
         view.set_axis_value(InputReceiver::GamepadAxis(axis), duration, state);
     }
-}
 
-/// Input system responsible for handling gamepad input and setting the button state for each updated button and axis.
-pub(crate) fn gamepad_input_system<Keys>(
-    mut query: Query<(&mut InputView<Keys>, &mut GamepadMarker)>,
-    mut rd: EventReader<GamepadEvent>,
-) where
-    Keys: BindingTypeView,
-{
-    for ev in rd.iter() {
+    /// Process a raw button value with the trigger dead zone and thresholds, then update the view.
+    pub fn handle_button<Keys>(
+        &mut self,
+        view: &mut InputView<Keys>,
+        button: GamepadButtonType,
+        value: f32,
+    ) where
+        Keys: BindingTypeView,
+    {
+        let (value, threshold) = match button {
+            GamepadButtonType::LeftTrigger2 | GamepadButtonType::RightTrigger2 => (
+                self.trigger_dead_zone.apply_axis(value),
+                self.trigger_press_threshold,
+            ),
+            _ => (value, self.button_press_threshold),
+        };
+        self.set_gamepad_button_state(view, button, press_state(value, threshold), value);
+    }
+
+    /// Process a raw axis value of the gamepad of this marker with the dead zones and thresholds, then update the
+    /// view. Stick axes are processed in pairs, so both axes of a stick are updated when one of them changes.
+    pub fn handle_axis<Keys>(
+        &mut self,
+        view: &mut InputView<Keys>,
+        axis: GamepadAxisType,
+        value: f32,
+    ) where
+        Keys: BindingTypeView,
+    {
+        self.handle_gamepad_axis::<Keys>(view, self.gamepad, axis, value);
+    }
+
+    /// Process a raw axis value of the given gamepad, updating both axes of a stick from that gamepad only.
+    fn handle_gamepad_axis<Keys>(
+        &mut self,
+        view: &mut InputView<Keys>,
+        gamepad: Gamepad,
+        axis: GamepadAxisType,
+        value: f32,
+    ) where
+        Keys: BindingTypeView,
+    {
+        use GamepadAxisType::*;
+
+        let left = match axis {
+            LeftStickX | LeftStickY => true,
+            RightStickX | RightStickY => false,
+            LeftZ | RightZ => {
+                let value = self.trigger_dead_zone.apply_axis(value);
+                let state = press_state(value, self.trigger_press_threshold);
+                self.set_gamepad_axis_state(view, axis, state, value);
+                return;
+            }
+            DPadX | DPadY => {
+                let state = press_state(value, self.button_press_threshold);
+                self.set_gamepad_axis_state(view, axis, state, value);
+                return;
+            }
+        };
+        let sticks = self.raw_sticks.entry(gamepad).or_default();
+        let raw = if left { &mut sticks.0 } else { &mut sticks.1 };
+        if matches!(axis, LeftStickX | RightStickX) {
+            raw.x = value;
+        } else {
+            raw.y = value;
+        }
+        let raw = *raw;
+        self.set_stick::<Keys>(view, axis, raw);
+    }
+
+    /// Process both raw axes of the stick the given axis belongs to with the dead zone, then update the view.
+    fn set_stick<Keys>(&mut self, view: &mut InputView<Keys>, axis: GamepadAxisType, raw: Vec2)
+    where
+        Keys: BindingTypeView,
+    {
+        use GamepadAxisType::*;
+
+        let (x, y) = match axis {
+            LeftStickX | LeftStickY => (LeftStickX, LeftStickY),
+            _ => (RightStickX, RightStickY),
+        };
+        let stick = self.dead_zone.apply(raw);
+        let threshold = self.stick_press_threshold;
+        self.set_gamepad_axis_state(view, x, press_state(stick.x, threshold), stick.x);
+        self.set_gamepad_axis_state(view, y, press_state(stick.y, threshold), stick.y);
+    }
+
+    /// Handle a gamepad event, ignoring events from gamepads not accepted by this marker.
+    pub fn handle_event<Keys>(&mut self, view: &mut InputView<Keys>, ev: &GamepadEvent)
+    where
+        Keys: BindingTypeView,
+    {
+        if !self.accepts(ev.0) {
+            return;
+        }
         match ev.1 {
+            GamepadEventType::ButtonChanged(kind, duration) if self.any_gamepad => {
+                self.raw_buttons.insert((ev.0, kind), duration);
+                self.handle_any_button::<Keys>(view, kind);
+            }
             GamepadEventType::ButtonChanged(kind, duration) => {
-                for (mut view, mut svc) in query.iter_mut() {
-                    if ev.0 != svc.gamepad {
-                        continue;
-                    }
-                    let state = if duration.abs() <= 0.1 {
-                        PressState::Released
-                    } else {
-                        PressState::Pressed {
-                            started_pressing_instant: None,
-                        }
-                    };
-                    svc.set_gamepad_button_state::<Keys>(view.as_mut(), kind, state, duration);
-                    break;
-                }
+                self.handle_button::<Keys>(view, kind, duration);
             }
             GamepadEventType::AxisChanged(kind, value) => {
-                for (mut view, mut svc) in query.iter_mut() {
-                    if ev.0 != svc.gamepad {
-                        continue;
-                    }
-                    let state = if value.abs() <= 0.1 {
-                        PressState::Released
-                    } else {
-                        PressState::Pressed {
-                            started_pressing_instant: None,
-                        }
-                    };
-                    if state.pressed()
-                        && match kind {
-                            GamepadAxisType::LeftStickX | GamepadAxisType::RightStickX => {
-                                value.abs() < svc.dead_zone.x
-                            }
-                            GamepadAxisType::LeftStickY | GamepadAxisType::RightStickY => {
-                                value.abs() < svc.dead_zone.y
-                            }
-                            _ => false,
-                        }
-                    {
-                        continue;
+                self.handle_gamepad_axis::<Keys>(view, ev.0, kind, value);
+            }
+            // A marker accepting any gamepad keeps the buttons held on the other connected gamepads, and moves a
+            // stick deflected on the disconnected gamepad to the most deflected one left, or centres it.
+            GamepadEventType::Disconnected if self.any_gamepad => {
+                if let Some((left, right)) = self.raw_sticks.remove(&ev.0) {
+                    let strongest = |stick: fn(&(Vec2, Vec2)) -> Vec2| {
+                        self.raw_sticks
+                            .values()
+                            .map(stick)
+                            .max_by(|a, b| a.length().total_cmp(&b.length()))
+                            .unwrap_or(Vec2::ZERO)
                     };
-                    svc.set_gamepad_axis_state::<Keys>(view.as_mut(), kind, state, value);
-                    break;
+                    let (new_left, new_right) = (strongest(|s| s.0), strongest(|s| s.1));
+                    if left != Vec2::ZERO {
+                        self.set_stick::<Keys>(view, GamepadAxisType::LeftStickX, new_left);
+                    }
+                    if right != Vec2::ZERO {
+                        self.set_stick::<Keys>(view, GamepadAxisType::RightStickX, new_right);
+                    }
+                }
+                let mut released = Vec::new();
+                self.raw_buttons.retain(|(gamepad, kind), _| {
+                    if *gamepad == ev.0 {
+                        released.push(*kind);
+                    }
+                    *gamepad != ev.0
+                });
+                for kind in released {
+                    self.handle_any_button::<Keys>(view, kind);
                 }
             }
-            _ => {}
+            GamepadEventType::Disconnected => {
+                self.release_all::<Keys>(view);
+            }
+            GamepadEventType::Connected => {}
+        }
+    }
+
+    /// Update a button from the strongest value it has on any gamepad, so releasing it on one gamepad doesn't
+    /// release it while another one still holds it.
+    fn handle_any_button<Keys>(&mut self, view: &mut InputView<Keys>, button: GamepadButtonType)
+    where
+        Keys: BindingTypeView,
+    {
+        let value = self
+            .raw_buttons
+            .iter()
+            .filter(|((_, kind), _)| *kind == button)
+            .map(|(_, value)| *value)
+            .fold(0., f32::max);
+        self.handle_button::<Keys>(view, button, value);
+    }
+
+    /// Release every gamepad button and axis of the view, e.g. when the gamepad is disconnected, so no input is
+    /// stuck until it is connected again.
+    pub fn release_all<Keys>(&mut self, view: &mut InputView<Keys>)
+    where
+        Keys: BindingTypeView,
+    {
+        let pressed: Vec<InputReceiver> = view
+            .descriptors
+            .iter()
+            .filter(|dsc| dsc.input.source() == InputSource::Gamepad && dsc.axis.press.pressed())
+            .map(|dsc| dsc.input)
+            .collect();
+        for rcv in pressed {
+            view.set_axis_value(rcv, 0., PressState::Released);
+        }
+        self.raw_sticks.clear();
+        self.raw_buttons.clear();
+    }
+}
+
+/// Entities listening to live gamepad input: neither waiting for a gamepad to be assigned nor being replayed.
+type LiveGamepadFilter = (Without<AwaitingGamepad>, Without<InputReplay>);
+
+/// Input system responsible for handling gamepad input and setting the button state for each updated button and axis.
+/// Entities still waiting for a gamepad to be assigned and entities being replayed are skipped.
+pub(crate) fn gamepad_input_system<Keys>(
+    mut query: Query<(&mut InputView<Keys>, &mut GamepadMarker), LiveGamepadFilter>,
+    mut rd: EventReader<GamepadEvent>,
+) where
+    Keys: BindingTypeView,
+{
+    let events: Vec<GamepadEvent> = rd.iter().cloned().collect();
+    for (mut view, mut svc) in query.iter_mut() {
+        for ev in events.iter() {
+            svc.handle_event::<Keys>(view.as_mut(), ev);
         }
     }
 }
+
+// Test to check if leaving the dead zone doesn't jump and going back into it doesn't leave drift.
+#[test]
+fn gamepad_dead_zone_test() {
+    #[derive(crate::BindingTypeView, PartialEq, Eq, Hash, Clone, Copy, Debug)]
+    enum Action {
+        Move,
+    }
+    let mut view = InputView::new();
+    view.add_binding(ActionBinding::from(Action::Move).dual_axis(DualAxis::left_stick()));
+    let mut marker = GamepadMarker::default()
+        .stick_dead_zone(DeadZone::scaled_radial(0.2))
+        .press_thresholds(0., 0.);
+    let x = InputReceiver::GamepadAxis(GamepadAxisType::LeftStickX);
+
+    marker.handle_axis(&mut view, GamepadAxisType::LeftStickX, 0.6);
+    assert!((view.axis_pair(&Action::Move).x - 0.5).abs() < 1e-5);
+    marker.handle_axis(&mut view, GamepadAxisType::LeftStickX, 0.21);
+    assert!(view.state(&x).value < 0.02 && view.state(&x).press.pressed());
+    marker.handle_axis(&mut view, GamepadAxisType::LeftStickX, 0.19);
+    assert_eq!(view.state(&x).value, 0.);
+    assert!(view.state(&x).press.released());
+}
+
+// Test to check if every view listening to a gamepad receives its input, including views accepting any gamepad,
+// which keep a button pressed until no gamepad holds it and keep the sticks of each gamepad apart.
+#[test]
+fn gamepad_routing_test() {
+    use bevy::{
+        ecs::event::Events,
+        prelude::{Stage, SystemStage, World},
+    };
+
+    #[derive(crate::BindingTypeView, PartialEq, Eq, Hash, Clone, Copy, Debug)]
+    enum Action {
+        Confirm,
+    }
+    let view = || {
+        let mut view = InputView::new();
+        view.add_binding(&mut ActionBinding::new_from_vec(
+            Action::Confirm,
+            vec![vec![GamepadButtonType::South.into()]],
+        ));
+        view
+    };
+    let mut world = World::new();
+    world.insert_resource(Events::<GamepadEvent>::default());
+    let entities = [
+        world
+            .spawn()
+            .insert(view())
+            .insert(GamepadMarker::with_id(0))
+            .id(),
+        world
+            .spawn()
+            .insert(view())
+            .insert(GamepadMarker::with_id(0))
+            .id(),
+        world
+            .spawn()
+            .insert(view())
+            .insert(GamepadMarker::with_id(1))
+            .id(),
+        world
+            .spawn()
+            .insert(view())
+            .insert(GamepadMarker::any())
+            .id(),
+    ];
+    world
+        .resource_mut::<Events<GamepadEvent>>()
+        .send(GamepadEvent(
+            Gamepad(0),
+            GamepadEventType::ButtonChanged(GamepadButtonType::South, 1.),
+        ));
+    let mut stage = SystemStage::single(gamepad_input_system::<Action>);
+    stage.run(&mut world);
+
+    let pressed = |world: &World| -> Vec<bool> {
+        entities
+            .iter()
+            .map(|entity| {
+                let view = world.get::<InputView<Action>>(*entity).unwrap();
+                view.key(&Action::Confirm).pressed()
+            })
+            .collect()
+    };
+    assert_eq!(pressed(&world), vec![true, true, false, true]);
+
+    let mut send = |world: &mut World, gamepad: usize, event_type: GamepadEventType| {
+        world
+            .resource_mut::<Events<GamepadEvent>>()
+            .send(GamepadEvent(Gamepad(gamepad), event_type));
+        stage.run(world);
+    };
+    let south = |value| GamepadEventType::ButtonChanged(GamepadButtonType::South, value);
+    send(&mut world, 1, south(1.));
+    send(&mut world, 0, south(0.));
+    assert_eq!(pressed(&world), vec![false, false, true, true]);
+    send(&mut world, 1, GamepadEventType::Disconnected);
+    assert_eq!(pressed(&world), vec![false, false, false, false]);
+
+    // Sticks of different gamepads aren't mixed, and a stick deflected when its gamepad is unplugged is moved
+    // to the gamepads left.
+    let stick = |world: &World| -> Vec2 {
+        let view = world.get::<InputView<Action>>(entities[3]).unwrap();
+        Vec2::new(
+            view.state(&GamepadAxisType::LeftStickX.into()).value,
+            view.state(&GamepadAxisType::LeftStickY.into()).value,
+        )
+    };
+    let axis = GamepadEventType::AxisChanged;
+    send(&mut world, 0, axis(GamepadAxisType::LeftStickX, 0.8));
+    send(&mut world, 2, axis(GamepadAxisType::LeftStickY, 0.5));
+    assert_eq!(stick(&world), Vec2::new(0., 0.5));
+    send(&mut world, 2, GamepadEventType::Disconnected);
+    assert_eq!(stick(&world), Vec2::new(0.8, 0.));
+    send(&mut world, 0, GamepadEventType::Disconnected);
+    assert_eq!(stick(&world), Vec2::ZERO);
+}
@@ -0,0 +1,125 @@
+//! A global input view, stored as a resource and driven by every built-in input source. Useful for menus, pause
+//! screens and debug tools, which can read `Res<InputView<Keys>>` without spawning an entity for it.
+//!
+//! Enable it with [`EZInputPlugin::global`]. The global view accepts input from every gamepad, and custom sources
+//! feed it once a [`GlobalCustomInput`] of the source is inserted. It isn't covered by action events, rebinding,
+//! binding profiles or replays, which work on entities.
+use std::marker::PhantomData;
+
+use bevy::{
+    input::{gamepad::GamepadEvent, keyboard::KeyboardInput, touch::TouchInput},
+    prelude::{EventReader, ResMut, SystemLabel},
+};
+
+use crate::prelude::*;
+
+#[derive(SystemLabel, Clone, Hash, Debug, PartialEq, Eq)]
+pub struct GlobalInputHandlingSystem;
+
+/// The markers handling input for the global view of the given keys.
+#[derive(PartialEq, Debug, Clone)]
+pub struct GlobalInputMarkers<Keys>
+where
+    Keys: BindingTypeView,
+{
+    pub keyboard: KeyboardMarker,
+    pub mouse: MouseMarker,
+    pub gamepad: GamepadMarker,
+    pub touch: TouchMarker,
+    phantom_keys: PhantomData<Keys>,
+}
+
+impl<Keys> Default for GlobalInputMarkers<Keys>
+where
+    Keys: BindingTypeView,
+{
+    fn default() -> Self {
+        Self {
+            keyboard: KeyboardMarker,
+            mouse: MouseMarker::default(),
+            gamepad: GamepadMarker::any(),
+            touch: TouchMarker::default(),
+            phantom_keys: PhantomData,
+        }
+    }
+}
+
+/// Input system responsible for handling input from every source for the global view.
+pub(crate) fn global_input_system<Keys>(
+    mut view: ResMut<InputView<Keys>>,
+    mut markers: ResMut<GlobalInputMarkers<Keys>>,
+    mut keyboard_rd: EventReader<KeyboardInput>,
+    mut mouse_rd: MouseEventReaders,
+    mut gamepad_rd: EventReader<GamepadEvent>,
+    mut touch_rd: EventReader<TouchInput>,
+) where
+    Keys: BindingTypeView,
+{
+    let view = view.as_mut();
+    let markers = markers.as_mut();
+    let keyboard_events: Vec<KeyboardInput> = keyboard_rd.iter().cloned().collect();
+    markers.keyboard.handle_events(view, &keyboard_events);
+    markers.mouse.handle_events(view, &mouse_rd.collect());
+    for ev in gamepad_rd.iter() {
+        markers.gamepad.handle_event(view, ev);
+    }
+    let touch_events: Vec<TouchInput> = touch_rd.iter().copied().collect();
+    markers.touch.handle_events(view, &touch_events);
+}
+
+// Test to check if the global view is driven by events without any entity.
+#[test]
+fn global_input_test() {
+    use bevy::{
+        ecs::event::Events,
+        input::{mouse::*, ElementState},
+        prelude::{KeyCode, Stage, SystemStage, World},
+        window::CursorMoved,
+    };
+
+    #[derive(crate::BindingTypeView, PartialEq, Eq, Hash, Clone, Copy, Debug)]
+    enum Action {
+        Pause,
+    }
+    let mut view = InputView::new();
+    view.add_binding(&mut ActionBinding::new_from_vec(
+        Action::Pause,
+        vec![
+            vec![KeyCode::Escape.into()],
+            vec![GamepadButtonType::Start.into()],
+        ],
+    ));
+    let mut world = World::new();
+    world.insert_resource(view);
+    world.insert_resource(GlobalInputMarkers::<Action>::default());
+    world.insert_resource(Events::<KeyboardInput>::default());
+    world.insert_resource(Events::<CursorMoved>::default());
+    world.insert_resource(Events::<MouseMotion>::default());
+    world.insert_resource(Events::<MouseButtonInput>::default());
+    world.insert_resource(Events::<MouseWheel>::default());
+    world.insert_resource(Events::<GamepadEvent>::default());
+    world.insert_resource(Events::<TouchInput>::default());
+    let mut stage = SystemStage::single(global_input_system::<Action>);
+
+    world
+        .resource_mut::<Events<GamepadEvent>>()
+        .send(GamepadEvent(
+            bevy::prelude::Gamepad(3),
+            bevy::prelude::GamepadEventType::ButtonChanged(GamepadButtonType::Start, 1.),
+        ));
+    stage.run(&mut world);
+    let view = world.resource::<InputView<Action>>();
+    assert!(view.key(&Action::Pause).pressed());
+    assert_eq!(view.last_input_source, Some(InputSource::Gamepad));
+
+    world
+        .resource_mut::<Events<KeyboardInput>>()
+        .send(KeyboardInput {
+            scan_code: 1,
+            key_code: Some(KeyCode::Escape),
+            state: ElementState::Pressed,
+        });
+    stage.run(&mut world);
+    let view = world.resource::<InputView<Action>>();
+    assert_eq!(view.last_input_source, Some(InputSource::Keyboard));
+}
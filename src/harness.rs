@@ -0,0 +1,74 @@
+//! Shared setup for the unit tests that drive a single input system over an input view.
+use crate::prelude::*;
+use bevy::{
+    ecs::schedule::IntoSystemDescriptor,
+    prelude::{Entity, Mut, Stage, SystemStage, World},
+    utils::Duration,
+};
+
+/// The state of a receiver pressed in the current tick.
+pub(crate) const PRESSED: PressState = PressState::Pressed {
+    started_pressing_instant: None,
+};
+
+/// A world with an entity holding an input view, and a stage running the system under test.
+pub(crate) struct ViewHarness<Keys>
+where
+    Keys: BindingTypeView,
+{
+    pub world: World,
+    pub entity: Entity,
+    stage: SystemStage,
+    global: bool,
+    _keys: std::marker::PhantomData<Keys>,
+}
+
+impl<Keys> ViewHarness<Keys>
+where
+    Keys: BindingTypeView,
+{
+    /// Spawn the view and prepare the stage running the given system.
+    pub fn new<Params>(view: InputView<Keys>, system: impl IntoSystemDescriptor<Params>) -> Self {
+        let mut world = World::new();
+        let entity = world.spawn().insert(view).id();
+        Self {
+            world,
+            entity,
+            stage: SystemStage::single(system),
+            global: false,
+            _keys: Default::default(),
+        }
+    }
+
+    /// Also insert a copy of the view as the global view, which is stepped along with the entity.
+    pub fn with_global(mut self) -> Self {
+        let view = self.view().clone();
+        self.world.insert_resource(view);
+        self.global = true;
+        self
+    }
+
+    /// Advance the view by a tick and the given time, apply the receiver changes and run the system.
+    pub fn tick(&mut self, millis: u64, changes: &[(InputReceiver, PressState)]) {
+        let step = |view: &mut InputView<Keys>| {
+            view.tick += 1;
+            view.time += Duration::from_millis(millis);
+            for (receiver, state) in changes {
+                view.set_key_receiver_state(*receiver, *state);
+            }
+        };
+        step(self.view_mut().as_mut());
+        if self.global {
+            step(self.world.resource_mut::<InputView<Keys>>().as_mut());
+        }
+        self.stage.run(&mut self.world);
+    }
+
+    pub fn view(&self) -> &InputView<Keys> {
+        self.world.get::<InputView<Keys>>(self.entity).unwrap()
+    }
+
+    pub fn view_mut(&mut self) -> Mut<'_, InputView<Keys>> {
+        self.world.get_mut::<InputView<Keys>>(self.entity).unwrap()
+    }
+}
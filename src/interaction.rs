@@ -0,0 +1,301 @@
+//! Declarative interactions, such as taps, multi-taps, holds and long presses, evaluated once per tick by the
+//! plugin for every binding declaring them with [`ActionBinding::interaction`].
+//!
+//! Triggered interactions are available through [`InputView::triggered`] and its shortcuts during the tick they
+//! were triggered, and are also emitted as [`ActionEventKind::Interaction`] events.
+use bevy::{
+    prelude::{Query, ResMut, SystemLabel},
+    utils::{Duration, HashMap},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+
+#[derive(SystemLabel, Clone, Hash, Debug, PartialEq, Eq)]
+pub struct InteractionSystem;
+
+/// An interaction with an action. Every duration is measured by the [`crate::clock::InputClock`].
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Deserialize, Serialize)]
+pub enum InputInteraction {
+    /// Triggered when the action is released before being pressed for more than the given duration.
+    Tap { max_duration: Duration },
+    /// Triggered when the action is tapped the given number of times, each tap starting before the given window
+    /// has elapsed since the previous one ended.
+    MultiTap {
+        count: u32,
+        max_duration: Duration,
+        window: Duration,
+    },
+    /// Triggered once while the action is pressed for the given duration.
+    Hold { duration: Duration },
+    /// Triggered when the action is released after being pressed for the given duration. The progress towards
+    /// the duration is available through [`InputView::long_press_progress`].
+    LongPress { duration: Duration },
+}
+
+impl InputInteraction {
+    /// Creates a multi-tap interaction triggered by two taps.
+    pub fn double_tap(max_duration: Duration, window: Duration) -> Self {
+        InputInteraction::MultiTap {
+            count: 2,
+            max_duration,
+            window,
+        }
+    }
+}
+
+/// The runtime state of a single interaction.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub(crate) struct InteractionState {
+    taps: u32,
+    last_tap: Duration,
+    fired: bool,
+}
+
+/// The runtime state of the interactions of a binding.
+#[derive(PartialEq, Clone, Debug, Default)]
+pub(crate) struct InteractionStates {
+    pressed_at: Option<Duration>,
+    states: Vec<InteractionState>,
+    triggered: Vec<InputInteraction>,
+    tick: u64,
+}
+
+/// The interaction states of every binding of a view.
+pub(crate) type InteractionStateMap<Keys> = HashMap<Keys, InteractionStates>;
+
+impl<Keys> ActionBinding<Keys>
+where
+    Keys: BindingTypeView,
+{
+    /// Add an interaction to this action.
+    pub fn interaction(&mut self, interaction: InputInteraction) -> &mut Self {
+        self.interactions.push(interaction);
+        self
+    }
+}
+
+impl<Keys> InputView<Keys>
+where
+    Keys: BindingTypeView,
+{
+    /// Returns the interactions of the given key triggered in the current tick.
+    pub fn triggered(&self, kind: &Keys) -> &[InputInteraction] {
+        match self.interaction_states.get(kind) {
+            Some(states) if states.tick == self.tick => &states.triggered,
+            _ => &[],
+        }
+    }
+
+    /// Returns whether a tap of the given key was triggered in the current tick.
+    pub fn tapped(&self, kind: &Keys) -> bool {
+        self.triggered(kind)
+            .iter()
+            .any(|interaction| matches!(interaction, InputInteraction::Tap { .. }))
+    }
+
+    /// Returns whether a multi-tap of the given key with the given number of taps was triggered in the current
+    /// tick.
+    pub fn multi_tapped(&self, kind: &Keys, taps: u32) -> bool {
+        self.triggered(kind).iter().any(|interaction| {
+            matches!(interaction, InputInteraction::MultiTap { count, .. } if *count == taps)
+        })
+    }
+
+    /// Returns whether a hold of the given key was triggered in the current tick.
+    pub fn held(&self, kind: &Keys) -> bool {
+        self.triggered(kind)
+            .iter()
+            .any(|interaction| matches!(interaction, InputInteraction::Hold { .. }))
+    }
+
+    /// Returns whether a long press of the given key was triggered in the current tick.
+    pub fn long_pressed(&self, kind: &Keys) -> bool {
+        self.triggered(kind)
+            .iter()
+            .any(|interaction| matches!(interaction, InputInteraction::LongPress { .. }))
+    }
+
+    /// Returns the progress, from zero to one, of the first long press of the given key while it is pressed.
+    pub fn long_press_progress(&self, kind: &Keys) -> Option<f32> {
+        let pressed_at = self.interaction_states.get(kind)?.pressed_at?;
+        let duration =
+            self.bindings.get(kind)?.interactions.iter().find_map(
+                |interaction| match interaction {
+                    InputInteraction::LongPress { duration } => Some(*duration),
+                    _ => None,
+                },
+            )?;
+        if duration.is_zero() {
+            return Some(1.);
+        }
+        let elapsed = self.time.saturating_sub(pressed_at);
+        Some((elapsed.as_secs_f32() / duration.as_secs_f32()).min(1.))
+    }
+
+    /// Returns the number of consecutive taps of the given key counted by its multi-tap interactions.
+    pub fn tap_count(&self, kind: &Keys) -> u32 {
+        self.interaction_states
+            .get(kind)
+            .and_then(|states| states.states.iter().map(|state| state.taps).max())
+            .unwrap_or(0)
+    }
+
+    /// Evaluate the interactions of every binding for the current tick.
+    pub(crate) fn update_interactions(&mut self) {
+        let (tick, time) = (self.tick, self.time);
+        for key in self.binding_order.clone() {
+            let interactions = match self.bindings.get(&key) {
+                Some(binding) if !binding.interactions.is_empty() => binding.interactions.clone(),
+                _ => {
+                    self.interaction_states.remove(&key);
+                    continue;
+                }
+            };
+            let (just_pressed, just_released, pressed) = (
+                self.just_pressed(&key),
+                self.just_released(&key),
+                self.key(&key).pressed(),
+            );
+            let states = self.interaction_states.entry(key).or_default();
+            states
+                .states
+                .resize(interactions.len(), InteractionState::default());
+            states.triggered.clear();
+            states.tick = tick;
+            if just_pressed {
+                states.pressed_at = Some(time);
+            }
+            let held = states
+                .pressed_at
+                .map_or(Duration::ZERO, |pressed_at| time.saturating_sub(pressed_at));
+
+            for (interaction, state) in interactions.iter().zip(states.states.iter_mut()) {
+                let triggered = match *interaction {
+                    InputInteraction::Tap { max_duration } => just_released && held <= max_duration,
+                    InputInteraction::MultiTap {
+                        count,
+                        max_duration,
+                        window,
+                    } => {
+                        if state.taps > 0
+                            && (just_pressed || !pressed)
+                            && time.saturating_sub(state.last_tap) > window
+                        {
+                            state.taps = 0;
+                        }
+                        if just_released && held <= max_duration {
+                            state.taps += 1;
+                            state.last_tap = time;
+                        } else if just_released {
+                            state.taps = 0;
+                        }
+                        let triggered = state.taps >= count;
+                        if triggered {
+                            state.taps = 0;
+                        }
+                        triggered
+                    }
+                    InputInteraction::Hold { duration } => {
+                        if just_pressed {
+                            state.fired = false;
+                        }
+                        let triggered = pressed && !state.fired && held >= duration;
+                        state.fired |= triggered;
+                        triggered
+                    }
+                    InputInteraction::LongPress { duration } => just_released && held >= duration,
+                };
+                if triggered {
+                    states.triggered.push(*interaction);
+                }
+            }
+            if !pressed {
+                states.pressed_at = None;
+            }
+        }
+    }
+}
+
+/// Evaluate the interactions of every view.
+pub(crate) fn interaction_system<Keys>(
+    mut query: Query<&mut InputView<Keys>>,
+    global: Option<ResMut<InputView<Keys>>>,
+) where
+    Keys: BindingTypeView,
+{
+    for mut view in query.iter_mut() {
+        view.update_interactions();
+    }
+    if let Some(mut view) = global {
+        view.update_interactions();
+    }
+}
+
+// Test to check if taps, double taps, holds and long presses are triggered only in the right tick.
+#[test]
+fn interaction_test() {
+    use crate::harness::{ViewHarness, PRESSED};
+    use bevy::prelude::KeyCode;
+
+    #[derive(crate::BindingTypeView, PartialEq, Eq, Hash, Clone, Copy, Debug)]
+    enum Action {
+        Attack,
+    }
+    let space = InputReceiver::KeyboardKey(KeyCode::Space);
+    let (pressed, released) = (
+        &[(space, PRESSED)][..],
+        &[(space, PressState::Released)][..],
+    );
+    let millis = Duration::from_millis;
+    let mut view = InputView::new();
+    view.add_binding(
+        ActionBinding::new_from_vec(Action::Attack, vec![vec![space]])
+            .interaction(InputInteraction::Tap {
+                max_duration: millis(200),
+            })
+            .interaction(InputInteraction::double_tap(millis(200), millis(300)))
+            .interaction(InputInteraction::Hold {
+                duration: millis(500),
+            })
+            .interaction(InputInteraction::LongPress {
+                duration: millis(1000),
+            }),
+    );
+
+    let mut harness = ViewHarness::new(view, interaction_system::<Action>);
+    let tick = |harness: &mut ViewHarness<Action>,
+                elapsed: u64,
+                changes: &[(InputReceiver, PressState)]| {
+        harness.tick(elapsed, changes);
+        harness.view().triggered(&Action::Attack).to_vec()
+    };
+
+    assert!(tick(&mut harness, 100, pressed).is_empty());
+    let triggered = tick(&mut harness, 100, released);
+    assert_eq!(
+        triggered,
+        vec![InputInteraction::Tap {
+            max_duration: millis(200)
+        }]
+    );
+    let view = harness.view();
+    assert_eq!(view.tap_count(&Action::Attack), 1);
+    tick(&mut harness, 100, pressed);
+    tick(&mut harness, 100, released);
+    let view = harness.view();
+    assert!(view.tapped(&Action::Attack) && view.multi_tapped(&Action::Attack, 2));
+    assert_eq!(view.tap_count(&Action::Attack), 0);
+
+    tick(&mut harness, 1000, pressed);
+    assert!(tick(&mut harness, 400, &[]).is_empty());
+    let view = harness.view();
+    assert_eq!(view.long_press_progress(&Action::Attack), Some(0.4));
+    tick(&mut harness, 100, &[]);
+    let view = harness.view();
+    assert!(view.held(&Action::Attack));
+    assert!(tick(&mut harness, 100, &[]).is_empty());
+    tick(&mut harness, 400, released);
+    let view = harness.view();
+    assert!(view.long_pressed(&Action::Attack) && !view.tapped(&Action::Attack));
+}
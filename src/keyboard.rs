@@ -22,18 +22,28 @@ impl KeyboardMarker {
         view.last_input_source = Some(InputSource::Keyboard);
         view.set_key_receiver_state(InputReceiver::KeyboardKey(key), state);
     }
+
+    /// Handle the keyboard events of the current tick.
+    pub fn handle_events<Keys>(&mut self, view: &mut InputView<Keys>, events: &[KeyboardInput])
+    where
+        Keys: BindingTypeView,
+    {
+        for ev in events.iter() {
+            if let Some(key) = ev.key_code {
+                self.set_keyboard_key_state::<Keys>(view, key, ev.state.into());
+            }
+        }
+    }
 }
 
 /// Input system responsible for handling keyboard input and setting the button state for each updated button and axis.
+/// Entities being replayed are skipped.
 pub(crate) fn keyboard_input_system<Keys: BindingTypeView>(
-    mut query: Query<(&mut InputView<Keys>, &mut KeyboardMarker)>,
+    mut query: Query<(&mut InputView<Keys>, &mut KeyboardMarker), Without<InputReplay>>,
     mut rd: EventReader<KeyboardInput>,
 ) {
+    let events: Vec<KeyboardInput> = rd.iter().cloned().collect();
     for (mut view, mut keyboard_svc) in query.iter_mut() {
-        for ev in rd.iter() {
-            if let Some(key) = ev.key_code {
-                keyboard_svc.set_keyboard_key_state::<Keys>(&mut view, key, ev.state.into());
-            }
-        }
+        keyboard_svc.handle_events::<Keys>(&mut view, &events);
     }
 }
@@ -1,30 +1,82 @@
 #![forbid(unsafe_code)]
 #![doc = include_str!("../README.md")]
 
+// Lets the derive macros refer to this crate by name from within it.
+extern crate self as ezinput;
+
+pub mod accessibility;
+pub mod actions;
+pub mod assignment;
 pub mod binding;
+pub mod buffer;
 pub mod bundle;
+pub mod clock;
+pub mod conflict;
+pub mod context;
+pub mod custom;
+pub mod dead_zone;
+pub mod dual_axis;
+pub mod event;
 pub mod gamepad;
+pub mod global;
+pub mod interaction;
 pub mod keyboard;
 pub mod macros;
 pub mod mouse;
 pub mod plugin;
-pub mod state;
+pub mod processor;
+pub mod profile;
+pub mod rebind;
 pub mod receiver;
+pub mod replay;
+pub mod sequence;
+pub mod simulator;
+pub mod snapshot;
+pub mod state;
+pub mod touch;
 pub mod view;
+
+#[cfg(test)]
+mod harness;
+
 pub use ezinput_macros::*;
+#[doc(hidden)]
+pub use serde;
 
 pub mod prelude {
+    pub use crate::accessibility::*;
+    pub use crate::actions::*;
+    pub use crate::assignment::*;
     pub use crate::binding::*;
+    pub use crate::buffer::*;
     pub use crate::bundle::*;
+    pub use crate::clock::*;
+    pub use crate::conflict::*;
+    pub use crate::context::*;
+    pub use crate::custom::*;
+    pub use crate::dead_zone::*;
+    pub use crate::dual_axis::*;
+    pub use crate::event::*;
     pub use crate::gamepad::*;
+    pub use crate::global::*;
+    pub use crate::interaction::*;
     pub use crate::keyboard::*;
     pub use crate::macros::*;
     pub use crate::mouse::*;
     pub use crate::plugin::*;
-    pub use crate::state::*;
+    pub use crate::processor::*;
+    pub use crate::profile::*;
+    pub use crate::rebind::*;
+    pub use crate::receiver::InputReceiver::*;
     pub use crate::receiver::*;
+    pub use crate::replay::*;
+    pub use crate::sequence::*;
+    pub use crate::simulator::*;
+    pub use crate::snapshot::*;
+    pub use crate::state::*;
+    pub use crate::touch::*;
     pub use crate::view::*;
+    pub use crate::Actions;
     pub use crate::BindingTypeView;
-    pub use crate::receiver::InputReceiver::*;
     pub use bevy::prelude::{GamepadAxisType, GamepadButtonType, KeyCode, MouseButton};
 }
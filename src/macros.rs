@@ -1,48 +1,68 @@
 //! Quality of life macros, usually for reducing boilerplate.
 
 /// A macro that generates input-related enumerations for easier use in ezinput.
+/// See [`crate::actions`] for a derive supporting doc comments, attributes and nesting at any depth.
 ///
 /// ## Examples
 ///
 /// This code:
-/// ```rust
+/// ```
+/// # use bevy::utils::Duration;
+/// use ezinput::prelude::*;
+///
 /// input! {
 ///     EnumeratedBinding {
 ///         Movement<EnumeratedMovementBinding> {
 ///             Vertical = [KeyboardKey(KeyCode::W), KeyboardKey(KeyCode::S) => -1., GamepadAxis(GamepadAxisType::LeftStickY)],
 ///             Horizontal = [KeyboardKey(KeyCode::A) => -1. /* default axis value */, KeyboardKey(KeyCode::D), GamepadAxis(GamepadAxisType::LeftStickX)],
+///             Move = [] axis_pair [DualAxis::wasd(), DualAxis::left_stick()] => AxisPairNormalization::Circle,
+///             Dash = [] sequence(Duration::from_millis(300)) [KeyboardKey(KeyCode::D), KeyboardKey(KeyCode::D)],
 ///         },
 ///     }
 /// }
+/// # let view = EnumeratedBinding::view();
+/// # assert_eq!(view.bindings.len(), 4);
 /// ```
 /// produces the code below:
-/// ```rust
+/// ```
+/// # use bevy::utils::Duration;
+/// # use ezinput::prelude::*;
 /// #[derive(BindingTypeView, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 /// pub enum EnumeratedBinding {
 ///     Movement(EnumeratedMovementBinding),
 /// }
-
+///
 /// #[derive(BindingTypeView, Debug, Clone, Copy, PartialEq, Eq, Hash)]
 /// pub enum EnumeratedMovementBinding {
 ///     Vertical,
 ///     Horizontal,
+///     Move,
+///     Dash,
 /// }
-
+///
 /// impl EnumeratedBinding {
 ///     pub fn view() -> InputView<Self> {
 ///         let mut view = InputView::new();
-///         EnumeratdMovementBinding::apply(&mut view);
+///         EnumeratedMovementBinding::apply(&mut view);
 ///         view
 ///     }
 /// }
 /// impl EnumeratedMovementBinding {
 ///     pub fn apply(view: &mut InputView<EnumeratedBinding>) {
 ///         let mut binding = ActionBinding::from(EnumeratedBinding::Movement(EnumeratedMovementBinding::Vertical));
-///         binding.receiver(KeyboardKey(KeyCode::W));
-///         binding.receiver(KeyboardKey(KeyCode::S));
-///         binding.default_axis_value(KeyboardKey(KeyCode::S), -1);
-///         view.add_binding(binding);
+///         binding.receivers(InputReceivers::from(vec![KeyboardKey(KeyCode::W)]));
+///         binding.receivers(InputReceivers::from(vec![KeyboardKey(KeyCode::S)]));
+///         binding.default_axis_value(KeyboardKey(KeyCode::S), -1.);
+///         view.add_binding(&mut binding);
 ///         // ...
+///         let mut binding = ActionBinding::from(EnumeratedBinding::Movement(EnumeratedMovementBinding::Move));
+///         binding.dual_axis(DualAxis::wasd());
+///         binding.dual_axis(DualAxis::left_stick());
+///         binding.axis_pair_normalization(AxisPairNormalization::Circle);
+///         view.add_binding(&mut binding);
+///         let mut binding = ActionBinding::from(EnumeratedBinding::Movement(EnumeratedMovementBinding::Dash));
+///         binding.sequence(Sequence::new(vec![/* steps */], Duration::from_millis(300)));
+///         view.add_binding(&mut binding);
 ///     }
 /// }
 /// ```
@@ -52,17 +72,23 @@ macro_rules! input {
     {
         $name:ident {
             $($category:ident<$category_enum:ident> {
-                $($key:ident = [$($kind:expr $(=> $default:expr)?),* $(,)?]),* $(,)?
+                $($key:ident = [$($kind:expr $(=> $default:expr)?),* $(,)?]
+                    $(sequence($gap:expr) [$($step:expr),* $(,)?])?
+                    $(axis_pair [$($pair:expr),* $(,)?] $(=> $normalization:expr)?)?),* $(,)?
             }),* $(,)?
         }
     } => {
 
         #[derive(ezinput::BindingTypeView, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #[derive(ezinput::serde::Serialize, ezinput::serde::Deserialize)]
+        #[serde(crate = "ezinput::serde")]
         pub enum $name {
             $($category($category_enum)),*
         }
         $(
             #[derive(ezinput::BindingTypeView, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+            #[derive(ezinput::serde::Serialize, ezinput::serde::Deserialize)]
+            #[serde(crate = "ezinput::serde")]
             pub enum $category_enum {
                 $($key),*
             }
@@ -79,6 +105,16 @@ macro_rules! input {
                                 }
                             )?
                         )*
+                        $(
+                            binding.sequence(ezinput::prelude::Sequence::new(
+                                vec![$(InputReceivers::from($step.into_receiver_vec())),*],
+                                $gap,
+                            ));
+                        )?
+                        $(
+                            $(binding.dual_axis($pair);)*
+                            $(binding.axis_pair_normalization($normalization);)?
+                        )?
                         input.add_binding(&mut binding);
                     )*
                 }
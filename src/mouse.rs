@@ -4,9 +4,10 @@ use std::hash::Hash;
 
 use crate::prelude::*;
 use bevy::{
+    ecs::system::SystemParam,
     input::mouse::{MouseButtonInput, MouseMotion, MouseWheel},
     math::Vec2,
-    prelude::{Component, EventReader, MouseButton, Query, SystemLabel},
+    prelude::{Component, EventReader, MouseButton, Query, SystemLabel, Without},
     window::CursorMoved,
 };
 use serde::{Deserialize, Serialize};
@@ -72,45 +73,36 @@ impl MouseMarker {
         view.last_input_source = Some(InputSource::Mouse);
     }
 
-    /// Tick the mouse by stop moving the axis when released.
+    /// Tick the mouse by releasing every axis that hasn't moved in the current tick. This needs to be called
+    /// after all mouse events for the tick are handled, so moving axes aren't released and pressed again.
     pub fn tick_mouse<Keys>(&mut self, view: &mut InputView<Keys>)
     where
         Keys: BindingTypeView,
     {
-        view.descriptor_or_insert(InputReceiver::MouseAxis(MouseAxisType::X))
-            .axis
-            .press = PressState::Released;
-        view.descriptor_or_insert(InputReceiver::MouseAxis(MouseAxisType::Y))
-            .axis
-            .press = PressState::Released;
-        view.set_axis_value(
-            InputReceiver::MouseAxis(MouseAxisType::Y),
-            0.,
-            PressState::Released,
-        );
-        view.set_axis_value(
-            InputReceiver::MouseAxis(MouseAxisType::Wheel),
-            0.,
-            PressState::Released,
-        );
-        view.set_axis_value(
-            InputReceiver::MouseAxisDelta(MouseAxisType::X),
-            0.,
-            PressState::Released,
-        );
-        view.set_axis_value(
-            InputReceiver::MouseAxisDelta(MouseAxisType::Y),
-            0.,
-            PressState::Released,
-        );
-        view.set_axis_value(
-            InputReceiver::MouseAxisDelta(MouseAxisType::Wheel),
-            0.,
-            PressState::Released,
-        );
-        self.does_mouse_location_changed_this_tick = false;
-        self.does_mouse_wheel_changed_this_tick = false;
-        self.mouse_delta = None;
+        if !self.does_mouse_location_changed_this_tick {
+            for axis in [MouseAxisType::X, MouseAxisType::Y] {
+                let value = view.state(&InputReceiver::MouseAxis(axis)).value;
+                view.set_axis_value(InputReceiver::MouseAxis(axis), value, PressState::Released);
+                view.set_axis_value(
+                    InputReceiver::MouseAxisDelta(axis),
+                    0.,
+                    PressState::Released,
+                );
+            }
+            self.mouse_delta = None;
+        }
+        if !self.does_mouse_wheel_changed_this_tick {
+            view.set_axis_value(
+                InputReceiver::MouseAxis(MouseAxisType::Wheel),
+                0.,
+                PressState::Released,
+            );
+            view.set_axis_value(
+                InputReceiver::MouseAxisDelta(MouseAxisType::Wheel),
+                0.,
+                PressState::Released,
+            );
+        }
     }
 
     /// Set the mouse button state for the given button and set the last input source to Mouse.
@@ -141,36 +133,75 @@ impl MouseMarker {
     }
 }
 
-/// Input system responsible for handling mouse input and setting the button state for each updated button and axis.
-pub(crate) fn mouse_input_system<Keys>(
-    mut query: Query<(&mut InputView<Keys>, &mut MouseMarker)>,
-    mut cursor_rd: EventReader<CursorMoved>,
-    mut btn_rd: EventReader<MouseButtonInput>,
-    mut mtn_rd: EventReader<MouseMotion>,
-    mut wheel_rd: EventReader<MouseWheel>,
-) where
-    Keys: BindingTypeView,
-{
-    for (mut view, mut mouse_svc) in query.iter_mut() {
-        let view = view.as_mut();
-        let mouse_svc = mouse_svc.as_mut();
-        mouse_svc.tick_mouse(view);
+/// The mouse events of a tick.
+#[derive(Debug, Clone, Default)]
+pub struct MouseEvents {
+    pub cursor: Vec<CursorMoved>,
+    pub motion: Vec<MouseMotion>,
+    pub buttons: Vec<MouseButtonInput>,
+    pub wheel: Vec<MouseWheel>,
+}
+
+/// The readers for every mouse event, collected into [`MouseEvents`] once per tick.
+#[derive(SystemParam)]
+pub(crate) struct MouseEventReaders<'w, 's> {
+    cursor: EventReader<'w, 's, CursorMoved>,
+    motion: EventReader<'w, 's, MouseMotion>,
+    buttons: EventReader<'w, 's, MouseButtonInput>,
+    wheel: EventReader<'w, 's, MouseWheel>,
+}
 
-        for (abs_position, delta) in cursor_rd.iter().zip(mtn_rd.iter()) {
-            mouse_svc.set_mouse_location(view, abs_position.position, delta.delta);
+impl<'w, 's> MouseEventReaders<'w, 's> {
+    /// Collect the events not read yet.
+    pub(crate) fn collect(&mut self) -> MouseEvents {
+        MouseEvents {
+            cursor: self.cursor.iter().cloned().collect(),
+            motion: self.motion.iter().cloned().collect(),
+            buttons: self.buttons.iter().cloned().collect(),
+            wheel: self.wheel.iter().cloned().collect(),
         }
-        for ev in btn_rd.iter() {
-            mouse_svc.set_mouse_button_state(view, ev.button, ev.state.into());
+    }
+}
+
+impl MouseMarker {
+    /// Handle the mouse events of the current tick, then tick the mouse.
+    pub fn handle_events<Keys>(&mut self, view: &mut InputView<Keys>, events: &MouseEvents)
+    where
+        Keys: BindingTypeView,
+    {
+        self.does_mouse_location_changed_this_tick = false;
+        self.does_mouse_wheel_changed_this_tick = false;
+
+        for (abs_position, delta) in events.cursor.iter().zip(events.motion.iter()) {
+            self.set_mouse_location(view, abs_position.position, delta.delta);
+        }
+        for ev in events.buttons.iter() {
+            self.set_mouse_button_state(view, ev.button, ev.state.into());
         }
-        for ev in wheel_rd.iter() {
+        for ev in events.wheel.iter() {
             let state = if ev.y > 0. {
                 PressState::Pressed {
                     started_pressing_instant: None,
                 }
             } else {
-                PressState::Released    
+                PressState::Released
             };
-            mouse_svc.set_mouse_wheel_state(view, ev.y, state);
+            self.set_mouse_wheel_state(view, ev.y, state);
         }
+        self.tick_mouse(view);
+    }
+}
+
+/// Input system responsible for handling mouse input and setting the button state for each updated button and axis.
+/// Entities being replayed are skipped.
+pub(crate) fn mouse_input_system<Keys>(
+    mut query: Query<(&mut InputView<Keys>, &mut MouseMarker), Without<InputReplay>>,
+    mut readers: MouseEventReaders,
+) where
+    Keys: BindingTypeView,
+{
+    let events = readers.collect();
+    for (mut view, mut mouse_svc) in query.iter_mut() {
+        mouse_svc.handle_events(view.as_mut(), &events);
     }
 }
@@ -4,19 +4,26 @@
 use std::marker::PhantomData;
 
 use crate::prelude::*;
+use bevy::ecs::schedule::ParallelSystemDescriptor;
 use bevy::input::InputSystem;
 use bevy::prelude::*;
-use bevy::utils::Instant;
 
 #[derive(SystemLabel, Clone, Hash, Debug, PartialEq, Eq)]
 pub struct InputHandlingTickSystem;
 
+#[derive(SystemLabel, Clone, Hash, Debug, PartialEq, Eq)]
+pub struct InputClockSystem;
+
+/// Marker resource letting other plugin instances know that the clock system is already registered.
+struct InputClockRegistered;
+
 /// A [`Plugin`] that handles [`Input`] from different type of input sources.
 pub struct EZInputPlugin<Keys>
 where
     Keys: BindingTypeView,
 {
     phantom_keys: PhantomData<Keys>,
+    global: Option<InputView<Keys>>,
 }
 
 impl<Keys> Default for EZInputPlugin<Keys>
@@ -26,6 +33,27 @@ where
     fn default() -> Self {
         Self {
             phantom_keys: PhantomData,
+            global: None,
+        }
+    }
+}
+
+impl<Keys> EZInputPlugin<Keys>
+where
+    Keys: BindingTypeView,
+{
+    /// Creates a plugin that also handles a global [`InputView`] resource, driven by every input source. An
+    /// empty view is inserted unless the resource already exists.
+    pub fn global() -> Self {
+        Self::global_with(InputView::new())
+    }
+
+    /// Creates a plugin that also handles a global [`InputView`] resource, inserting the given view unless the
+    /// resource already exists.
+    pub fn global_with(view: InputView<Keys>) -> Self {
+        Self {
+            phantom_keys: PhantomData,
+            global: Some(view),
         }
     }
 }
@@ -42,12 +70,42 @@ where
         ) {
             app.add_system_to_stage(
                 CoreStage::PreUpdate,
-                func.before(InputHandlingTickSystem).after(InputSystem),
+                func.after(InputHandlingTickSystem).after(InputSystem),
             );
         }
+        /// Order a system after the systems applying input to the views.
+        fn after_handling<Params>(
+            func: impl ParallelSystemDescriptorCoercion<Params>,
+        ) -> ParallelSystemDescriptor {
+            func.after(KeyboardInputHandlingSystem)
+                .after(MouseInputHandlingSystem)
+                .after(GamepadInputHandlingSystem)
+                .after(TouchInputHandlingSystem)
+                .after(InputReplaySystem)
+        }
+        // The clock is shared between every plugin instance, so it must only be advanced once per frame.
+        if !app.world.contains_resource::<InputClockRegistered>() {
+            app.init_resource::<InputClock>()
+                .insert_resource(InputClockRegistered)
+                .add_system_to_stage(CoreStage::PreUpdate, clock_system.label(InputClockSystem));
+        }
+        // Gamepads are assigned to entities regardless of their keys, so the assignment system is shared too.
+        if !app.world.contains_resource::<GamepadAssignments>() {
+            app.init_resource::<GamepadAssignments>()
+                .add_event::<GamepadAssignmentEvent>()
+                .add_system_to_stage(
+                    CoreStage::PreUpdate,
+                    gamepad_assignment_system
+                        .label(GamepadAssignmentSystem)
+                        .after(InputSystem),
+                );
+        }
         app.add_system_to_stage(
             CoreStage::PreUpdate,
-            tick_system::<Keys>.label(InputHandlingTickSystem),
+            tick_system::<Keys>
+                .label(InputHandlingTickSystem)
+                .after(InputSystem)
+                .after(InputClockSystem),
         );
         add_handling_system(
             app,
@@ -59,28 +117,116 @@ where
         );
         add_handling_system(
             app,
-            gamepad_input_system::<Keys>.label(GamepadInputHandlingSystem),
+            gamepad_input_system::<Keys>
+                .label(GamepadInputHandlingSystem)
+                .after(GamepadAssignmentSystem),
+        );
+        add_handling_system(
+            app,
+            touch_input_system::<Keys>.label(TouchInputHandlingSystem),
+        );
+        add_handling_system(app, input_replay_system::<Keys>.label(InputReplaySystem));
+        if let Some(view) = &self.global {
+            if !app.world.contains_resource::<InputView<Keys>>() {
+                app.insert_resource(view.clone());
+            }
+            app.init_resource::<GlobalInputMarkers<Keys>>()
+                .add_system_to_stage(
+                    CoreStage::PreUpdate,
+                    global_input_system::<Keys>
+                        .label(GlobalInputHandlingSystem)
+                        .after(InputHandlingTickSystem)
+                        .after(InputSystem)
+                        .before(SequenceSystem)
+                        .before(AxisProcessingSystem),
+                );
+        }
+        app.add_event::<RebindEvent<Keys>>().add_system_to_stage(
+            CoreStage::PreUpdate,
+            after_handling(rebinding_system::<Keys>.label(RebindingSystem)),
+        );
+        app.add_system_to_stage(
+            CoreStage::PreUpdate,
+            after_handling(sequence_system::<Keys>.label(SequenceSystem))
+                .before(AxisProcessingSystem),
+        );
+        app.add_system_to_stage(
+            CoreStage::PreUpdate,
+            after_handling(axis_processing_system::<Keys>.label(AxisProcessingSystem)),
+        );
+        app.add_system_to_stage(
+            CoreStage::PreUpdate,
+            input_recording_system::<Keys>
+                .label(InputRecordingSystem)
+                .after(AxisProcessingSystem),
+        );
+        app.add_system_to_stage(
+            CoreStage::PreUpdate,
+            accessibility_system::<Keys>
+                .label(AccessibilitySystem)
+                .after(AxisProcessingSystem),
+        );
+        app.add_system_to_stage(
+            CoreStage::PreUpdate,
+            input_buffer_system::<Keys>
+                .label(InputBufferSystem)
+                .after(AccessibilitySystem),
+        );
+        app.add_system_to_stage(
+            CoreStage::PreUpdate,
+            interaction_system::<Keys>
+                .label(InteractionSystem)
+                .after(AccessibilitySystem),
+        );
+        app.add_system_to_stage(
+            CoreStage::PreUpdate,
+            input_snapshot_system::<Keys>
+                .label(InputSnapshotSystem)
+                .after(AccessibilitySystem),
+        );
+        app.add_event::<ActionEvent<Keys>>().add_system_to_stage(
+            CoreStage::PreUpdate,
+            action_event_system::<Keys>
+                .label(ActionEventSystem)
+                .after(AxisProcessingSystem)
+                .after(InteractionSystem),
         );
     }
 }
 
 /// Tick every input system to update the press state for the current time, letting the input view know the press
-/// state for the action.
+/// state for the action. This runs before the handling systems, so every state change applied by them in this
+/// frame is recorded with the new tick. Presses handled in the previous tick receive the clock time of that tick.
 #[doc(hidden)]
-fn tick_system<Keys>(mut query: Query<&mut InputView<Keys>>)
-where
+fn tick_system<Keys>(
+    mut query: Query<&mut InputView<Keys>>,
+    global: Option<ResMut<InputView<Keys>>>,
+    clock: Res<InputClock>,
+) where
     Keys: BindingTypeView,
 {
     for mut view in query.iter_mut() {
-        for ReceiverDescriptor { axis, .. } in view.descriptors.iter_mut() {
-            if let PressState::Pressed {
-                ref mut started_pressing_instant,
-            } = axis.press
-            {
-                if started_pressing_instant.is_none() {
-                    *started_pressing_instant = Some(Instant::now());
-                }
+        tick_view(view.as_mut(), &clock);
+    }
+    if let Some(mut view) = global {
+        tick_view(view.as_mut(), &clock);
+    }
+}
+
+fn tick_view<Keys>(view: &mut InputView<Keys>, clock: &InputClock)
+where
+    Keys: BindingTypeView,
+{
+    view.tick += 1;
+    for ReceiverDescriptor { axis, .. } in view.descriptors.iter_mut() {
+        if let PressState::Pressed {
+            ref mut started_pressing_instant,
+        } = axis.press
+        {
+            if started_pressing_instant.is_none() {
+                *started_pressing_instant = Some(view.time);
             }
         }
     }
+    view.time = clock.now();
 }
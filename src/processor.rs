@@ -0,0 +1,307 @@
+//! Axis processing pipelines, applying sensitivity, inversion, clamping and response curves to axis values
+//! when they are read from an [`InputView`]. Pipelines can be attached to a whole [`ActionBinding`] or to a
+//! single receiver of it, and are serialized along with the binding so player settings persist.
+use bevy::prelude::{Query, ResMut, SystemLabel};
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+
+#[derive(SystemLabel, Clone, Hash, Debug, PartialEq, Eq)]
+pub struct AxisProcessingSystem;
+
+/// A single step of an axis processing pipeline.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum AxisProcessor {
+    /// Multiply the value, e.g. for sensitivity.
+    Scale(f32),
+    /// Invert the direction of the value.
+    Invert,
+    /// Limit the value to a range.
+    Clamp { min: f32, max: f32 },
+    /// Raise the magnitude of the value to an exponent, keeping its direction. Exponents above one give more
+    /// precision near the center.
+    Power(f32),
+    /// Map the magnitude of the value through linearly interpolated `(input, output)` points, sorted by their
+    /// input, keeping its direction. Create it with [`AxisProcessor::piecewise`] to sort the points.
+    Piecewise(Vec<(f32, f32)>),
+    /// Move towards the value by the given factor (`0..1`) every tick. Since it depends on the previous
+    /// value, it returns the value computed by the processing system in the current tick. In the pipeline of an
+    /// action, it is only applied to the action value, that is, the last receiver of the pressed combination.
+    Smoothing {
+        factor: f32,
+        #[serde(skip)]
+        value: f32,
+    },
+}
+
+impl AxisProcessor {
+    /// Creates a smoothing processor starting at zero.
+    pub fn smoothing(factor: f32) -> Self {
+        AxisProcessor::Smoothing { factor, value: 0. }
+    }
+
+    /// Creates a piecewise curve from the given `(input, output)` points, sorting them by their input and keeping
+    /// the first point of each input. Points with a NaN input are dropped.
+    pub fn piecewise(points: impl IntoIterator<Item = (f32, f32)>) -> Self {
+        let mut points: Vec<(f32, f32)> = points
+            .into_iter()
+            .filter(|(input, _)| !input.is_nan())
+            .collect();
+        points.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+        points.dedup_by(|(b, _), (a, _)| a == b);
+        AxisProcessor::Piecewise(points)
+    }
+
+    /// Apply this processor to a value. Smoothing returns the value computed in the last tick.
+    pub fn apply(&self, value: f32) -> f32 {
+        match self {
+            AxisProcessor::Scale(scale) => value * scale,
+            AxisProcessor::Invert => -value,
+            AxisProcessor::Clamp { min, max } => value.clamp(*min, *max),
+            AxisProcessor::Power(exponent) => value.signum() * value.abs().powf(*exponent),
+            AxisProcessor::Piecewise(points) => {
+                let magnitude = value.abs();
+                let output = match points.iter().position(|(input, _)| *input >= magnitude) {
+                    None => points.last().map_or(magnitude, |(_, output)| *output),
+                    Some(0) => points[0].1,
+                    Some(index) => {
+                        let (x0, y0) = points[index - 1];
+                        let (x1, y1) = points[index];
+                        // Points that aren't sorted by their input have no range to interpolate in.
+                        if x1 > x0 {
+                            y0 + (y1 - y0) * (magnitude - x0) / (x1 - x0)
+                        } else {
+                            y1
+                        }
+                    }
+                };
+                value.signum() * output
+            }
+            AxisProcessor::Smoothing { value, .. } => *value,
+        }
+    }
+}
+
+/// Compare processors by their settings, ignoring the runtime state of smoothing processors.
+impl PartialEq for AxisProcessor {
+    fn eq(&self, other: &Self) -> bool {
+        use AxisProcessor::*;
+        match (self, other) {
+            (Scale(a), Scale(b)) | (Power(a), Power(b)) => a == b,
+            (Invert, Invert) => true,
+            (
+                Clamp { min, max },
+                Clamp {
+                    min: other_min,
+                    max: other_max,
+                },
+            ) => min == other_min && max == other_max,
+            (Piecewise(a), Piecewise(b)) => a == b,
+            (Smoothing { factor: a, .. }, Smoothing { factor: b, .. }) => a == b,
+            _ => false,
+        }
+    }
+}
+
+pub trait AxisProcessorExt {
+    /// Run a value through all processors.
+    fn process(&self, value: f32) -> f32;
+
+    /// Run a value through all processors but the smoothing ones.
+    fn process_unsmoothed(&self, value: f32) -> f32;
+
+    /// Run a value through all processors, updating the state of smoothing processors.
+    fn step(&mut self, value: f32) -> f32;
+}
+
+impl AxisProcessorExt for [AxisProcessor] {
+    fn process(&self, value: f32) -> f32 {
+        self.iter()
+            .fold(value, |value, processor| processor.apply(value))
+    }
+
+    fn process_unsmoothed(&self, value: f32) -> f32 {
+        self.iter()
+            .filter(|processor| !matches!(processor, AxisProcessor::Smoothing { .. }))
+            .fold(value, |value, processor| processor.apply(value))
+    }
+
+    fn step(&mut self, value: f32) -> f32 {
+        self.iter_mut().fold(value, |input, processor| {
+            if let AxisProcessor::Smoothing { factor, value } = processor {
+                *value += (input - *value) * factor.clamp(0., 1.);
+            }
+            processor.apply(input)
+        })
+    }
+}
+
+impl<Keys> ActionBinding<Keys>
+where
+    Keys: BindingTypeView,
+{
+    /// Add a processor to the pipeline of this action, applied to the values returned by [`InputView::axis`].
+    pub fn processor(&mut self, processor: AxisProcessor) -> &mut Self {
+        self.processors.push(processor);
+        self
+    }
+
+    /// Add a processor to the pipeline of a single receiver of this action, applied before the pipeline of the
+    /// action and also used by [`InputView::axis_pair`].
+    pub fn receiver_processor(
+        &mut self,
+        receiver: InputReceiver,
+        processor: AxisProcessor,
+    ) -> &mut Self {
+        self.receiver_processors
+            .entry(receiver)
+            .or_default()
+            .push(processor);
+        self
+    }
+
+    /// Run a value of the given receiver through its pipeline.
+    pub fn process_receiver(&self, receiver: &InputReceiver, value: f32) -> f32 {
+        self.receiver_processors
+            .get(receiver)
+            .map_or(value, |processors| processors.process(value))
+    }
+}
+
+/// Update the smoothing processors of every pipeline with the values of the current tick. Receiver pipelines
+/// are updated first, since the pipeline of the action receives their output.
+pub(crate) fn axis_processing_system<Keys>(
+    mut query: Query<&mut InputView<Keys>>,
+    global: Option<ResMut<InputView<Keys>>>,
+) where
+    Keys: BindingTypeView,
+{
+    for mut view in query.iter_mut() {
+        process_view(view.as_mut());
+    }
+    if let Some(mut view) = global {
+        process_view(view.as_mut());
+    }
+}
+
+fn process_view<Keys>(view: &mut InputView<Keys>)
+where
+    Keys: BindingTypeView,
+{
+    for binding in view.bindings.values_mut() {
+        for (rcv, processors) in binding.receiver_processors.iter_mut() {
+            let state = view
+                .descriptors
+                .iter()
+                .find(|dsc| dsc.input == *rcv)
+                .map_or(AxisState::ZERO, |dsc| dsc.axis);
+            let value = if state.press.pressed() {
+                state.value
+            } else {
+                0.
+            };
+            processors.step(value);
+        }
+    }
+
+    let values: Vec<(Keys, f32)> = view
+        .bindings
+        .keys()
+        .map(|key| {
+            let value = view
+                .receiver_axis(key)
+                .last()
+                .map_or(0., |state| state.value);
+            (*key, value)
+        })
+        .collect();
+    for (key, value) in values {
+        if let Some(binding) = view.bindings.get_mut(&key) {
+            binding.processors.step(value);
+        }
+    }
+}
+
+// Test to check if the processors are applied in order, both directly and when reading a view, and if piecewise
+// curves are sorted.
+#[test]
+fn axis_processor_test() {
+    use bevy::math::Vec2;
+
+    #[derive(
+        crate::BindingTypeView, PartialEq, Eq, Hash, Clone, Copy, Debug, Deserialize, Serialize,
+    )]
+    enum Action {
+        Look,
+    }
+    let curve = [
+        AxisProcessor::Piecewise(vec![(0., 0.), (0.5, 0.25), (1., 1.)]),
+        AxisProcessor::Invert,
+        AxisProcessor::Scale(2.),
+    ];
+    assert_eq!(curve.process(0.75), -1.25);
+    assert_eq!(curve.process(-0.25), 0.25);
+    assert_eq!(
+        AxisProcessor::piecewise([(1., 1.), (0.5, 0.25), (f32::NAN, 0.), (0., 0.), (0.5, 0.5)]),
+        curve[0]
+    );
+    let unsorted = AxisProcessor::Piecewise(vec![(0., 0.), (1., 1.), (0.5, 0.5), (0.5, 0.25)]);
+    assert!([0., 0.25, 0.5, 0.75, 1.]
+        .iter()
+        .all(|value| unsorted.apply(*value).is_finite()));
+    assert_eq!([AxisProcessor::Power(2.)].process(-0.5), -0.25);
+    assert_eq!(
+        [AxisProcessor::Clamp { min: 0., max: 0.5 }].process(0.75),
+        0.5
+    );
+
+    let mut smoothing = [AxisProcessor::smoothing(0.5)];
+    assert_eq!(smoothing.step(1.), 0.5);
+    assert_eq!(smoothing.step(1.), 0.75);
+    assert_eq!(smoothing.process(0.), 0.75);
+    assert_eq!(smoothing, [AxisProcessor::smoothing(0.5)]);
+
+    let (x, y) = (
+        InputReceiver::GamepadAxis(GamepadAxisType::RightStickX),
+        InputReceiver::GamepadAxis(GamepadAxisType::RightStickY),
+    );
+    let pressed = PressState::Pressed {
+        started_pressing_instant: None,
+    };
+    let mut view = InputView::new();
+    view.add_binding(
+        ActionBinding::new_from_vec(Action::Look, vec![vec![y]])
+            .dual_axis(DualAxis::right_stick())
+            .receiver_processor(y, AxisProcessor::Invert)
+            .processor(AxisProcessor::Scale(2.)),
+    );
+    view.set_axis_value(x, 0.5, pressed);
+    view.set_axis_value(y, 0.25, pressed);
+    assert_eq!(view.axis(&Action::Look)[0].value, -0.5);
+    assert_eq!(view.axis_pair(&Action::Look), Vec2::new(0.5, -0.25));
+
+    let mut chord = InputView::new();
+    chord.add_binding(
+        ActionBinding::new_from_vec(Action::Look, vec![vec![x, y]])
+            .processor(AxisProcessor::smoothing(0.5)),
+    );
+    chord.set_axis_value(x, 0.5, pressed);
+    chord.set_axis_value(y, 1., pressed);
+    process_view(&mut chord);
+    let values: Vec<f32> = chord
+        .axis(&Action::Look)
+        .iter()
+        .map(|state| state.value)
+        .collect();
+    assert_eq!(values, vec![0.5, 0.5]);
+    chord.set_axis_value(x, 0.25, pressed);
+    process_view(&mut chord);
+    assert_eq!(chord.axis(&Action::Look)[0].value, 0.25);
+    assert_eq!(chord.axis(&Action::Look)[1].value, 0.75);
+
+    let saved = BindingProfile::from_view(&view)
+        .to_string(ProfileFormat::Json)
+        .unwrap();
+    let loaded: BindingProfile<Action> = ProfileFormat::Json.deserialize(saved.as_bytes()).unwrap();
+    assert_eq!(loaded, BindingProfile::from_view(&view));
+}
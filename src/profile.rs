@@ -0,0 +1,534 @@
+//! Binding profiles, allowing the bindings of an [`InputView`] to be loaded from RON or JSON files through
+//! Bevy assets and hot-reloaded whenever the file changes, or exported back to a file (e.g. user rebindings).
+//!
+//! Attach a [`Handle<BindingProfileSource>`] to an entity with an input view, e.g.
+//! `commands.entity(player).insert(asset_server.load::<BindingProfileSource, _>("controls.input.ron"))`,
+//! and the bindings of the view will be replaced by the profile once it is loaded. Profiles are handled by the
+//! [`BindingProfilePlugin`], which needs the binding keys to implement `Deserialize` and must be added after
+//! the asset plugin (e.g. after `DefaultPlugins`).
+use std::{fmt::Display, marker::PhantomData, path::Path};
+
+use bevy::{
+    asset::{AssetEvent, AssetLoader, Assets, Handle, LoadContext, LoadedAsset},
+    log::warn,
+    prelude::{
+        AddAsset, App, AssetServer, ChangeTrackers, CoreStage, EventReader,
+        ParallelSystemDescriptorCoercion, Plugin, Query, Res, SystemLabel,
+    },
+    reflect::TypeUuid,
+    utils::BoxedFuture,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::prelude::*;
+
+#[derive(SystemLabel, Clone, Hash, Debug, PartialEq, Eq)]
+pub struct BindingProfileSystem;
+
+/// A serializable description of a single action, its receivers and its default axis values.
+#[derive(PartialEq, Clone, Debug, Deserialize, Serialize)]
+pub struct ActionProfile<Keys>
+where
+    Keys: BindingTypeView,
+{
+    pub action: Keys,
+    pub receivers: Vec<Vec<InputReceiver>>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub default_axis_value: Vec<(InputReceiver, f32)>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dual_axes: Vec<DualAxis>,
+    #[serde(default)]
+    pub axis_pair_normalization: AxisPairNormalization,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub processors: Vec<AxisProcessor>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub receiver_processors: Vec<(InputReceiver, Vec<AxisProcessor>)>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sequences: Vec<Sequence>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub buffer_window: Option<BufferWindow>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub interactions: Vec<InputInteraction>,
+    #[serde(default, skip_serializing_if = "AccessibilityOptions::is_default")]
+    pub accessibility: AccessibilityOptions,
+}
+
+/// A serializable set of bindings that can be applied to an [`InputView`].
+#[derive(PartialEq, Clone, Debug, Deserialize, Serialize)]
+pub struct BindingProfile<Keys>
+where
+    Keys: BindingTypeView,
+{
+    pub actions: Vec<ActionProfile<Keys>>,
+}
+
+impl<Keys> Default for BindingProfile<Keys>
+where
+    Keys: BindingTypeView,
+{
+    fn default() -> Self {
+        Self {
+            actions: Vec::new(),
+        }
+    }
+}
+
+impl<Keys> ActionProfile<Keys>
+where
+    Keys: BindingTypeView,
+{
+    /// Creates the profile of a binding, reading the default axis values of its receivers from the view.
+    /// Receivers are sorted, so the same binding always produces the same output.
+    pub fn from_binding(binding: &ActionBinding<Keys>, view: &InputView<Keys>) -> Self {
+        let mut receivers: Vec<Vec<InputReceiver>> = binding
+            .input_receivers
+            .iter()
+            .map(|rcvs| rcvs.0.clone())
+            .collect();
+        receivers.sort_by_cached_key(|rcvs| format!("{:?}", rcvs));
+
+        let mut default_axis_value: Vec<(InputReceiver, f32)> = Vec::new();
+        for rcv in receivers.iter().flatten() {
+            let value = binding
+                .default_axis_value
+                .get(rcv)
+                .copied()
+                .or_else(|| {
+                    view.descriptor(rcv)
+                        .map(|descriptor| descriptor.default_axis_value)
+                })
+                .unwrap_or(0.);
+            if value != 0. && !default_axis_value.iter().any(|(r, _)| r == rcv) {
+                default_axis_value.push((*rcv, value));
+            }
+        }
+
+        let mut receiver_processors: Vec<(InputReceiver, Vec<AxisProcessor>)> = binding
+            .receiver_processors
+            .iter()
+            .map(|(rcv, processors)| (*rcv, processors.clone()))
+            .collect();
+        receiver_processors.sort_by_cached_key(|(rcv, _)| format!("{:?}", rcv));
+
+        Self {
+            action: binding.key,
+            receivers,
+            default_axis_value,
+            dual_axes: binding.dual_axes.clone(),
+            axis_pair_normalization: binding.axis_pair_normalization,
+            processors: binding.processors.clone(),
+            receiver_processors,
+            sequences: binding.sequences.clone(),
+            buffer_window: binding.buffer_window,
+            interactions: binding.interactions.clone(),
+            accessibility: binding.accessibility,
+        }
+    }
+
+    /// Converts this profile into an action binding.
+    pub fn to_binding(&self) -> ActionBinding<Keys> {
+        let mut binding = ActionBinding::new_from_vec(self.action, self.receivers.clone());
+        for (receiver, value) in self.default_axis_value.iter() {
+            binding.default_axis_value(*receiver, *value);
+        }
+        binding.dual_axes = self.dual_axes.clone();
+        binding.axis_pair_normalization = self.axis_pair_normalization;
+        binding.processors = self.processors.clone();
+        binding.receiver_processors = self.receiver_processors.iter().cloned().collect();
+        binding.sequences = self.sequences.clone();
+        binding.buffer_window = self.buffer_window;
+        binding.interactions = self.interactions.clone();
+        binding.accessibility = self.accessibility;
+        binding
+    }
+}
+
+impl<Keys> BindingProfile<Keys>
+where
+    Keys: BindingTypeView,
+{
+    /// Export the current bindings of a view, including their default axis values. While contexts are pushed,
+    /// the base bindings below them are exported. Actions are sorted, so the same view always produces the same
+    /// output.
+    pub fn from_view(view: &InputView<Keys>) -> Self {
+        let bindings = match &view.base_bindings {
+            Some((base, _)) => base,
+            None => &view.bindings,
+        };
+        let mut actions: Vec<ActionProfile<Keys>> = bindings
+            .values()
+            .map(|binding| ActionProfile::from_binding(binding, view))
+            .collect();
+        actions.sort_by_cached_key(|action| format!("{:?}", action.action));
+        Self { actions }
+    }
+
+    /// Replace all bindings of the given view by the bindings of this profile. While contexts are pushed, the
+    /// base bindings below them are replaced.
+    pub fn apply(&self, view: &mut InputView<Keys>) {
+        for descriptor in view.descriptors.iter_mut() {
+            descriptor.default_axis_value = 0.;
+        }
+        view.edit_base_bindings(|bindings, order| {
+            bindings.clear();
+            order.clear();
+        });
+        self.merge(view);
+    }
+
+    /// Replace only the bindings of the actions in this profile, keeping every other binding of the view as
+    /// is, as well as the binding order. This is useful for applying a user override file over the default
+    /// bindings.
+    pub fn merge(&self, view: &mut InputView<Keys>) {
+        for action in self.actions.iter() {
+            view.replace_binding(&mut action.to_binding());
+        }
+    }
+
+    /// Serialize this profile in the given format.
+    pub fn to_string(&self, format: ProfileFormat) -> Result<String, ProfileError>
+    where
+        Keys: Serialize,
+    {
+        format.serialize(self)
+    }
+
+    /// Write this profile to a file, in the format matching its extension (RON by default).
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ProfileError>
+    where
+        Keys: Serialize,
+    {
+        let path = path.as_ref();
+        let format = ProfileFormat::from_path(path).unwrap_or(ProfileFormat::Ron);
+        std::fs::write(path, self.to_string(format)?).map_err(ProfileError::Io)
+    }
+
+    /// Read a profile from a file, in the format matching its extension (RON by default).
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ProfileError>
+    where
+        Keys: DeserializeOwned,
+    {
+        let path = path.as_ref();
+        let format = ProfileFormat::from_path(path).unwrap_or(ProfileFormat::Ron);
+        format.deserialize(&std::fs::read(path).map_err(ProfileError::Io)?)
+    }
+}
+
+/// The file format of a binding profile.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum ProfileFormat {
+    Ron,
+    Json,
+}
+
+impl ProfileFormat {
+    /// Returns the format matching the extension of the given path, if any.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "ron" => Some(ProfileFormat::Ron),
+            "json" => Some(ProfileFormat::Json),
+            _ => None,
+        }
+    }
+
+    /// Deserialize a value from bytes written in this format.
+    pub fn deserialize<T: DeserializeOwned>(&self, bytes: &[u8]) -> Result<T, ProfileError> {
+        match self {
+            ProfileFormat::Ron => ron::de::from_bytes(bytes).map_err(ProfileError::Ron),
+            ProfileFormat::Json => serde_json::from_slice(bytes).map_err(ProfileError::Json),
+        }
+    }
+
+    /// Serialize a value in this format, without any whitespace.
+    pub fn serialize_compact<T: Serialize>(&self, value: &T) -> Result<String, ProfileError> {
+        match self {
+            ProfileFormat::Ron => ron::ser::to_string(value).map_err(ProfileError::Ron),
+            ProfileFormat::Json => serde_json::to_string(value).map_err(ProfileError::Json),
+        }
+    }
+
+    /// Serialize a value in this format, in a human-readable way.
+    pub fn serialize<T: Serialize>(&self, value: &T) -> Result<String, ProfileError> {
+        match self {
+            ProfileFormat::Ron => {
+                ron::ser::to_string_pretty(value, Default::default()).map_err(ProfileError::Ron)
+            }
+            ProfileFormat::Json => serde_json::to_string_pretty(value).map_err(ProfileError::Json),
+        }
+    }
+}
+
+/// An error that happened while reading or writing a binding profile.
+#[derive(Debug)]
+pub enum ProfileError {
+    Ron(ron::Error),
+    Json(serde_json::Error),
+    Io(std::io::Error),
+}
+
+impl Display for ProfileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProfileError::Ron(err) => write!(f, "Invalid RON binding profile: {}", err),
+            ProfileError::Json(err) => write!(f, "Invalid JSON binding profile: {}", err),
+            ProfileError::Io(err) => write!(f, "Unable to access the binding profile: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ProfileError {}
+
+/// The asset representation of a binding profile file. Since assets can't be generic over the binding keys,
+/// the source is kept as is and deserialized into a [`BindingProfile`] when applied to a view.
+#[derive(PartialEq, Clone, Debug, TypeUuid)]
+#[uuid = "8f2e3f4c-4a0b-4d2e-9b4f-52c2f4f0a7d1"]
+pub struct BindingProfileSource {
+    pub format: ProfileFormat,
+    pub bytes: Vec<u8>,
+}
+
+impl BindingProfileSource {
+    /// Deserialize the profile for the given binding keys.
+    pub fn parse<Keys>(&self) -> Result<BindingProfile<Keys>, ProfileError>
+    where
+        Keys: BindingTypeView + DeserializeOwned,
+    {
+        self.format.deserialize(&self.bytes)
+    }
+}
+
+/// Asset loader for binding profiles written in RON (`.input.ron`) or JSON (`.input.json`).
+#[derive(Default)]
+pub struct BindingProfileLoader;
+
+impl AssetLoader for BindingProfileLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+        Box::pin(async move {
+            let format =
+                ProfileFormat::from_path(load_context.path()).unwrap_or(ProfileFormat::Ron);
+            // Validate the syntax early, so broken files are reported when they are loaded.
+            match format {
+                ProfileFormat::Ron => {
+                    format.deserialize::<ron::Value>(bytes)?;
+                }
+                ProfileFormat::Json => {
+                    format.deserialize::<serde_json::Value>(bytes)?;
+                }
+            }
+            load_context.set_default_asset(LoadedAsset::new(BindingProfileSource {
+                format,
+                bytes: bytes.to_vec(),
+            }));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["input.ron", "input.json"]
+    }
+}
+
+/// A [`Plugin`] applying the binding profiles attached to the views of the given keys. It must be added after
+/// the asset plugin, since profiles are loaded as assets.
+pub struct BindingProfilePlugin<Keys>
+where
+    Keys: BindingTypeView + DeserializeOwned,
+{
+    phantom_keys: PhantomData<Keys>,
+}
+
+impl<Keys> Default for BindingProfilePlugin<Keys>
+where
+    Keys: BindingTypeView + DeserializeOwned,
+{
+    fn default() -> Self {
+        Self {
+            phantom_keys: PhantomData,
+        }
+    }
+}
+
+impl<Keys> Plugin for BindingProfilePlugin<Keys>
+where
+    Keys: BindingTypeView + DeserializeOwned,
+{
+    fn build(&self, app: &mut App) {
+        if !app.world.contains_resource::<AssetServer>() {
+            warn!("binding profiles are disabled, add the asset plugin before the binding profile plugin");
+            return;
+        }
+        if !app
+            .world
+            .contains_resource::<Assets<BindingProfileSource>>()
+        {
+            app.add_asset::<BindingProfileSource>()
+                .init_asset_loader::<BindingProfileLoader>();
+        }
+        app.add_system_to_stage(
+            CoreStage::PreUpdate,
+            binding_profile_system::<Keys>
+                .label(BindingProfileSystem)
+                .before(InputHandlingTickSystem),
+        );
+    }
+}
+
+type ProfileHandle = Handle<BindingProfileSource>;
+
+/// Apply binding profiles to their views whenever they are loaded, modified or attached to a new entity.
+pub(crate) fn binding_profile_system<Keys>(
+    mut query: Query<(
+        &mut InputView<Keys>,
+        &ProfileHandle,
+        ChangeTrackers<ProfileHandle>,
+    )>,
+    mut events: EventReader<AssetEvent<BindingProfileSource>>,
+    assets: Res<Assets<BindingProfileSource>>,
+) where
+    Keys: BindingTypeView + DeserializeOwned,
+{
+    let changed: Vec<&ProfileHandle> = events
+        .iter()
+        .filter_map(|ev| match ev {
+            AssetEvent::Created { handle } | AssetEvent::Modified { handle } => Some(handle),
+            AssetEvent::Removed { .. } => None,
+        })
+        .collect();
+    for (mut view, handle, tracker) in query.iter_mut() {
+        if !changed.contains(&handle) && !tracker.is_changed() {
+            continue;
+        }
+        if let Some(source) = assets.get(handle) {
+            match source.parse::<Keys>() {
+                Ok(profile) => profile.apply(view.as_mut()),
+                Err(err) => warn!("{}", err),
+            }
+        }
+    }
+}
+
+// Test to check if the same profile written in RON and JSON is applied to a view.
+#[test]
+fn binding_profile_format_test() {
+    use bevy::prelude::KeyCode;
+
+    #[derive(
+        crate::BindingTypeView, PartialEq, Eq, Hash, Clone, Copy, Debug, Deserialize, Serialize,
+    )]
+    enum Action {
+        Vertical,
+    }
+    let ron = br#"(actions: [(
+        action: Vertical,
+        receivers: [[KeyboardKey(W)], [KeyboardKey(S)]],
+        default_axis_value: [(KeyboardKey(S), -1.0)],
+    )])"#;
+    let json = br#"{"actions": [{
+        "action": "Vertical",
+        "receivers": [[{"KeyboardKey": "W"}], [{"KeyboardKey": "S"}]],
+        "default_axis_value": [[{"KeyboardKey": "S"}, -1.0]]
+    }]}"#;
+    let ron: BindingProfile<Action> = ProfileFormat::Ron.deserialize(ron).unwrap();
+    let json: BindingProfile<Action> = ProfileFormat::Json.deserialize(json).unwrap();
+    assert_eq!(ron, json);
+
+    let mut view = InputView::new();
+    ron.apply(&mut view);
+    let s = InputReceiver::KeyboardKey(KeyCode::S);
+    assert_eq!(view.bindings[&Action::Vertical].input_receivers.len(), 2);
+    assert_eq!(view.descriptor(&s).unwrap().default_axis_value, -1.);
+}
+
+// Test to check if exporting a view and merging it over the defaults keeps the default axis values.
+#[test]
+fn binding_profile_round_trip_test() {
+    use bevy::prelude::KeyCode;
+
+    #[derive(
+        crate::BindingTypeView, PartialEq, Eq, Hash, Clone, Copy, Debug, Deserialize, Serialize,
+    )]
+    enum Action {
+        Vertical,
+        Jump,
+    }
+    let (w, s, up, space) = (
+        InputReceiver::KeyboardKey(KeyCode::W),
+        InputReceiver::KeyboardKey(KeyCode::S),
+        InputReceiver::KeyboardKey(KeyCode::Up),
+        InputReceiver::KeyboardKey(KeyCode::Space),
+    );
+    let defaults = || {
+        let mut view = InputView::new();
+        view.add_binding(
+            ActionBinding::new_from_vec(Action::Vertical, vec![vec![w], vec![s]])
+                .default_axis_value(s, -1.),
+        );
+        view.add_binding(&mut ActionBinding::new_from_vec(
+            Action::Jump,
+            vec![vec![space]],
+        ));
+        view
+    };
+
+    let mut user = defaults();
+    user.replace_binding(
+        ActionBinding::new_from_vec(Action::Vertical, vec![vec![up], vec![s]])
+            .default_axis_value(s, -1.),
+    );
+    let mut overrides = BindingProfile::from_view(&user);
+    overrides
+        .actions
+        .retain(|action| action.action == Action::Vertical);
+    let saved = overrides.to_string(ProfileFormat::Ron).unwrap();
+    let loaded: BindingProfile<Action> = ProfileFormat::Ron.deserialize(saved.as_bytes()).unwrap();
+    assert_eq!(loaded, overrides);
+
+    let mut view = defaults();
+    loaded.merge(&mut view);
+    assert_eq!(view.binding_order, vec![Action::Vertical, Action::Jump]);
+    assert_eq!(
+        BindingProfile::from_view(&view),
+        BindingProfile::from_view(&user)
+    );
+    assert_eq!(view.descriptor(&s).unwrap().default_axis_value, -1.);
+    assert!(view.bindings.contains_key(&Action::Jump));
+}
+
+// Test to check if saving a view while a context is pushed exports the base bindings, including the receivers
+// and default axis values the context overrides.
+#[test]
+fn binding_profile_context_test() {
+    use bevy::prelude::KeyCode;
+
+    #[derive(
+        crate::BindingTypeView, PartialEq, Eq, Hash, Clone, Copy, Debug, Deserialize, Serialize,
+    )]
+    enum Action {
+        Vertical,
+        Back,
+    }
+    let (w, s) = (
+        InputReceiver::KeyboardKey(KeyCode::W),
+        InputReceiver::KeyboardKey(KeyCode::S),
+    );
+    let mut view = InputView::new();
+    view.add_binding(
+        ActionBinding::new_from_vec(Action::Vertical, vec![vec![w], vec![s]])
+            .default_axis_value(s, -1.),
+    );
+    let saved = BindingProfile::from_view(&view);
+
+    view.push_context(
+        InputContext::new("menu").binding(
+            ActionBinding::new_from_vec(Action::Back, vec![vec![s]])
+                .default_axis_value(s, 1.)
+                .clone(),
+        ),
+    );
+    assert_eq!(view.bindings[&Action::Vertical].input_receivers.len(), 1);
+    assert_eq!(BindingProfile::from_view(&view), saved);
+}
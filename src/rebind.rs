@@ -0,0 +1,270 @@
+//! Interactive rebinding, capturing the next receivers pressed on an entity to bind them to an action. Useful
+//! for options menus where players press the key or button they want to use.
+//!
+//! Insert a [`Rebinding`] component into an entity with an input view to start capturing, and read the
+//! [`RebindEvent`] sent when the capture finishes.
+use bevy::{
+    prelude::{Commands, Component, Entity, EventWriter, KeyCode, Query, SystemLabel},
+    utils::Duration,
+};
+
+use crate::prelude::*;
+
+#[derive(SystemLabel, Clone, Hash, Debug, PartialEq, Eq)]
+pub struct RebindingSystem;
+
+/// A capture in progress for a specific action. The capture starts in the first tick it is seen by the
+/// rebinding system; receivers pressed before or during that tick, such as the key confirming the prompt, are
+/// ignored.
+#[derive(PartialEq, Clone, Debug, Component)]
+pub struct Rebinding<Keys>
+where
+    Keys: BindingTypeView,
+{
+    pub action: Keys,
+    /// The receivers combination that will be replaced by the captured one. When none, all receivers
+    /// of the action are replaced.
+    pub replacing: Option<InputReceivers>,
+    /// Receivers that are never captured.
+    pub excluded: Vec<InputReceiver>,
+    /// Receivers that cancel the capture when pressed.
+    pub cancel: Vec<InputReceiver>,
+    /// The maximum duration of the capture, measured by the [`crate::clock::InputClock`].
+    pub timeout: Option<Duration>,
+    /// Whether multiple receivers can be captured as a chord (e.g. Ctrl+S). Chords are finished when one
+    /// of their receivers is released; otherwise the capture finishes in the first press.
+    pub chords: bool,
+    held: Vec<InputReceiver>,
+    started: Option<(u64, Duration)>,
+}
+
+impl<Keys> Rebinding<Keys>
+where
+    Keys: BindingTypeView,
+{
+    /// Creates a capture for the given action. By default, mouse movement, the mouse wheel and touch movement are
+    /// excluded, Escape cancels the capture and chords are allowed.
+    pub fn new(action: Keys) -> Self {
+        Self {
+            action,
+            replacing: None,
+            excluded: vec![
+                InputReceiver::MouseAxis(MouseAxisType::X),
+                InputReceiver::MouseAxis(MouseAxisType::Y),
+                InputReceiver::MouseAxisDelta(MouseAxisType::X),
+                InputReceiver::MouseAxisDelta(MouseAxisType::Y),
+                InputReceiver::MouseAxis(MouseAxisType::Wheel),
+                InputReceiver::MouseAxisDelta(MouseAxisType::Wheel),
+                InputReceiver::TouchAxis(TouchAxisType::X),
+                InputReceiver::TouchAxis(TouchAxisType::Y),
+                InputReceiver::TouchAxisDelta(TouchAxisType::X),
+                InputReceiver::TouchAxisDelta(TouchAxisType::Y),
+            ],
+            cancel: vec![InputReceiver::KeyboardKey(KeyCode::Escape)],
+            timeout: None,
+            chords: true,
+            held: Vec::new(),
+            started: None,
+        }
+    }
+
+    /// Set the receivers combination that will be replaced by the captured one.
+    pub fn replacing(mut self, receivers: InputReceivers) -> Self {
+        self.replacing = Some(receivers);
+        self
+    }
+
+    /// Add a receiver that is never captured.
+    pub fn excluding(mut self, receiver: InputReceiver) -> Self {
+        self.excluded.push(receiver);
+        self
+    }
+
+    /// Set the receivers that cancel the capture.
+    pub fn cancel_with(mut self, receivers: Vec<InputReceiver>) -> Self {
+        self.cancel = receivers;
+        self
+    }
+
+    /// Set the maximum duration of the capture.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Set whether multiple receivers can be captured as a chord.
+    pub fn chords(mut self, chords: bool) -> Self {
+        self.chords = chords;
+        self
+    }
+}
+
+/// The outcome of a capture.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum RebindResult {
+    /// The receivers were captured, in the order they were pressed.
+    Captured(InputReceivers),
+    /// A cancel receiver was pressed.
+    Cancelled,
+    /// Nothing was captured before the timeout.
+    TimedOut,
+}
+
+/// An event sent when a capture finishes.
+#[derive(PartialEq, Clone, Debug)]
+pub struct RebindEvent<Keys>
+where
+    Keys: BindingTypeView,
+{
+    pub entity: Entity,
+    pub action: Keys,
+    pub replacing: Option<InputReceivers>,
+    pub result: RebindResult,
+}
+
+impl<Keys> RebindEvent<Keys>
+where
+    Keys: BindingTypeView,
+{
+    /// Apply the captured receivers to the binding of the action in the given view, returning whether the binding
+    /// was changed. While contexts are pushed, the base binding below them is changed.
+    pub fn apply(&self, view: &mut InputView<Keys>) -> bool {
+        let receivers = match &self.result {
+            RebindResult::Captured(receivers) => receivers.clone(),
+            _ => return false,
+        };
+        view.edit_base_bindings(|bindings, _| match bindings.get_mut(&self.action) {
+            Some(binding) => {
+                match &self.replacing {
+                    Some(replacing) => {
+                        binding.input_receivers.remove(replacing);
+                    }
+                    None => binding.input_receivers.clear(),
+                }
+                binding.receivers(receivers);
+                true
+            }
+            None => false,
+        })
+    }
+}
+
+/// Capture the receivers pressed on every entity with a [`Rebinding`] and send the result once finished.
+pub(crate) fn rebinding_system<Keys>(
+    mut commands: Commands,
+    mut query: Query<(Entity, &InputView<Keys>, &mut Rebinding<Keys>)>,
+    mut writer: EventWriter<RebindEvent<Keys>>,
+) where
+    Keys: BindingTypeView,
+{
+    for (entity, view, mut rebinding) in query.iter_mut() {
+        let (start_tick, start_time) = *rebinding.started.get_or_insert((view.tick, view.time));
+        // Receivers tapped within the current tick are captured too, and finish the capture right away.
+        let mut pressed: Vec<&ReceiverDescriptor> = view
+            .descriptors
+            .iter()
+            .filter(|dsc| {
+                (dsc.axis.press.pressed() || dsc.axis.pressed_on(view.tick))
+                    && dsc.axis.pressed_tick.is_some_and(|tick| tick > start_tick)
+            })
+            .collect();
+        pressed.sort_by_key(|dsc| dsc.axis.pressed_tick);
+
+        let result = if pressed
+            .iter()
+            .any(|dsc| rebinding.cancel.contains(&dsc.input))
+        {
+            Some(RebindResult::Cancelled)
+        } else {
+            for dsc in pressed {
+                if !rebinding.excluded.contains(&dsc.input) && !rebinding.held.contains(&dsc.input)
+                {
+                    rebinding.held.push(dsc.input);
+                }
+            }
+            let released = rebinding
+                .held
+                .iter()
+                .any(|rcv| view.state(rcv).press.released());
+            if !rebinding.held.is_empty() && (!rebinding.chords || released) {
+                let held = if rebinding.chords {
+                    rebinding.held.clone()
+                } else {
+                    rebinding.held[..1].to_vec()
+                };
+                Some(RebindResult::Captured(InputReceivers(held)))
+            } else if rebinding
+                .timeout
+                .is_some_and(|timeout| view.time.saturating_sub(start_time) >= timeout)
+            {
+                Some(RebindResult::TimedOut)
+            } else {
+                None
+            }
+        };
+
+        if let Some(result) = result {
+            writer.send(RebindEvent {
+                entity,
+                action: rebinding.action,
+                replacing: rebinding.replacing.clone(),
+                result,
+            });
+            commands.entity(entity).remove::<Rebinding<Keys>>();
+        }
+    }
+}
+
+// Test to check if a chord is captured in the order it was pressed once one of its keys is released, ignoring the
+// key starting the capture, and if a tap within a single tick is captured.
+#[test]
+fn rebinding_chord_test() {
+    use crate::harness::{ViewHarness, PRESSED};
+    use bevy::ecs::event::Events;
+
+    #[derive(crate::BindingTypeView, PartialEq, Eq, Hash, Clone, Copy, Debug)]
+    enum Action {
+        Save,
+    }
+    let (enter, ctrl, s) = (
+        InputReceiver::KeyboardKey(KeyCode::Return),
+        InputReceiver::KeyboardKey(KeyCode::LControl),
+        InputReceiver::KeyboardKey(KeyCode::S),
+    );
+    let mut harness = ViewHarness::new(InputView::<Action>::new(), rebinding_system::<Action>);
+    harness
+        .world
+        .insert_resource(Events::<RebindEvent<Action>>::default());
+    harness
+        .world
+        .entity_mut(harness.entity)
+        .insert(Rebinding::new(Action::Save));
+    let tick = |harness: &mut ViewHarness<Action>, changes: &[(InputReceiver, PressState)]| {
+        harness.tick(0, changes);
+        let mut events = harness.world.resource_mut::<Events<RebindEvent<Action>>>();
+        let results: Vec<_> = events.drain().map(|ev| ev.result).collect();
+        results
+    };
+
+    assert!(tick(&mut harness, &[(enter, PRESSED)]).is_empty());
+    assert!(tick(&mut harness, &[(ctrl, PRESSED)]).is_empty());
+    assert!(tick(&mut harness, &[(s, PRESSED)]).is_empty());
+    assert_eq!(
+        tick(&mut harness, &[(s, PressState::Released)]),
+        vec![RebindResult::Captured(InputReceivers(vec![ctrl, s]))]
+    );
+    assert!(harness
+        .world
+        .get::<Rebinding<Action>>(harness.entity)
+        .is_none());
+
+    harness
+        .world
+        .entity_mut(harness.entity)
+        .insert(Rebinding::new(Action::Save));
+    assert!(tick(&mut harness, &[(ctrl, PressState::Released)]).is_empty());
+    assert_eq!(
+        tick(&mut harness, &[(s, PRESSED), (s, PressState::Released)]),
+        vec![RebindResult::Captured(InputReceivers(vec![s]))]
+    );
+}
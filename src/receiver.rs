@@ -5,7 +5,10 @@ use std::fmt::Debug;
 use bevy::prelude::{GamepadAxisType, GamepadButtonType, KeyCode, MouseButton};
 use serde::{Deserialize, Serialize};
 
-use crate::{prelude::MouseAxisType, view::InputSource};
+use crate::{
+    prelude::{CustomSource, MouseAxisType, TouchAxisType},
+    view::InputSource,
+};
 
 /// A agnostic type, representing a type of input that can be accepted on ezinput systems.
 #[derive(PartialEq, Eq, Hash, Clone, Copy, Debug, Deserialize, Serialize)]
@@ -16,6 +19,15 @@ pub enum InputReceiver {
     MouseAxis(MouseAxisType),
     GamepadAxis(GamepadAxisType),
     MouseAxisDelta(MouseAxisType),
+    /// Pressed while the primary finger is touching the screen.
+    TouchPress,
+    TouchAxis(TouchAxisType),
+    TouchAxisDelta(TouchAxisType),
+    /// A [`crate::touch::VirtualButton`] of the touch marker, by its index.
+    TouchButton(u8),
+    TouchJoystick(TouchAxisType),
+    /// A receiver of a [`crate::custom::CustomInputSource`], by its identifier.
+    Custom(CustomSource, u32),
 }
 
 impl InputReceiver {
@@ -26,41 +38,54 @@ impl InputReceiver {
             InputReceiver::MouseButton(_)
             | InputReceiver::MouseAxis(_)
             | InputReceiver::MouseAxisDelta(_) => InputSource::Mouse,
+            InputReceiver::TouchPress
+            | InputReceiver::TouchAxis(_)
+            | InputReceiver::TouchAxisDelta(_)
+            | InputReceiver::TouchButton(_)
+            | InputReceiver::TouchJoystick(_) => InputSource::Touch,
+            InputReceiver::Custom(source, _) => InputSource::Custom(source),
         }
     }
 }
 
-impl Into<InputReceiver> for KeyCode {
-    fn into(self) -> InputReceiver {
-        InputReceiver::KeyboardKey(self)
+impl From<KeyCode> for InputReceiver {
+    fn from(value: KeyCode) -> Self {
+        InputReceiver::KeyboardKey(value)
     }
 }
 
-impl Into<InputReceiver> for MouseButton {
-    fn into(self) -> InputReceiver {
-        InputReceiver::MouseButton(self)
+impl From<MouseButton> for InputReceiver {
+    fn from(value: MouseButton) -> Self {
+        InputReceiver::MouseButton(value)
     }
 }
 
-impl Into<InputReceiver> for GamepadButtonType {
-    fn into(self) -> InputReceiver {
-        InputReceiver::GamepadButton(self)
+impl From<GamepadButtonType> for InputReceiver {
+    fn from(value: GamepadButtonType) -> Self {
+        InputReceiver::GamepadButton(value)
     }
 }
 
-impl Into<InputReceiver> for GamepadAxisType {
-    fn into(self) -> InputReceiver {
-        InputReceiver::GamepadAxis(self)
+impl From<GamepadAxisType> for InputReceiver {
+    fn from(value: GamepadAxisType) -> Self {
+        InputReceiver::GamepadAxis(value)
     }
 }
 
-impl Into<InputReceiver> for MouseAxisType {
-    fn into(self) -> InputReceiver {
-        InputReceiver::MouseAxis(self)
+impl From<MouseAxisType> for InputReceiver {
+    fn from(value: MouseAxisType) -> Self {
+        InputReceiver::MouseAxis(value)
+    }
+}
+
+impl From<TouchAxisType> for InputReceiver {
+    fn from(value: TouchAxisType) -> Self {
+        InputReceiver::TouchAxis(value)
     }
 }
 
 pub trait IntoReceiverVec {
+    #[allow(clippy::wrong_self_convention)]
     fn into_receiver_vec(&self) -> Vec<InputReceiver>;
 }
 
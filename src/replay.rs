@@ -0,0 +1,246 @@
+//! Recording and replaying input sessions, useful for reproducing bugs and automated playtesting.
+//!
+//! An entity with an [`InputRecorder`] records every receiver state change the handling systems apply to its
+//! [`InputView`], along with the time elapsed on the [`crate::clock::InputClock`] in every tick, starting with
+//! the tick after the recorder was inserted. An entity with an [`InputReplay`] is fed a recording back, tick by
+//! tick, instead of live input. Ticks are counted from the first tick of the recording or replay, and the view of
+//! a replayed entity follows the recorded time instead of the clock, so a replay is deterministic regardless of
+//! the frame rate. Remove the [`InputReplay`] to handle live input again.
+use std::path::Path;
+
+use bevy::{
+    prelude::{Component, Query, SystemLabel, Without},
+    utils::Duration,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+
+#[derive(SystemLabel, Clone, Hash, Debug, PartialEq, Eq)]
+pub struct InputRecordingSystem;
+
+#[derive(SystemLabel, Clone, Hash, Debug, PartialEq, Eq)]
+pub struct InputReplaySystem;
+
+/// A state change of a receiver: its value and whether it is pressed.
+#[derive(PartialEq, Clone, Copy, Debug, Deserialize, Serialize)]
+pub struct RecordedChange(pub InputReceiver, pub f32, pub bool);
+
+/// The time elapsed in a single tick, counted from the first tick of the recording, and the state changes applied
+/// in it.
+#[derive(PartialEq, Clone, Debug, Deserialize, Serialize)]
+pub struct RecordedTick(pub u64, pub Duration, pub Vec<RecordedChange>);
+
+/// A recorded input session, with every tick since the recording started.
+#[derive(PartialEq, Clone, Debug, Default, Deserialize, Serialize)]
+pub struct InputRecording {
+    pub ticks: Vec<RecordedTick>,
+}
+
+impl InputRecording {
+    /// Returns the number of ticks covered by this recording.
+    pub fn duration(&self) -> u64 {
+        self.ticks
+            .last()
+            .map_or(0, |RecordedTick(tick, ..)| tick + 1)
+    }
+
+    /// Serialize this recording in the given format, without any whitespace.
+    pub fn to_string(&self, format: ProfileFormat) -> Result<String, ProfileError> {
+        format.serialize_compact(self)
+    }
+
+    /// Write this recording to a file, in the format matching its extension (RON by default).
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ProfileError> {
+        let path = path.as_ref();
+        let format = ProfileFormat::from_path(path).unwrap_or(ProfileFormat::Ron);
+        std::fs::write(path, self.to_string(format)?).map_err(ProfileError::Io)
+    }
+
+    /// Read a recording from a file, in the format matching its extension (RON by default).
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ProfileError> {
+        let path = path.as_ref();
+        let format = ProfileFormat::from_path(path).unwrap_or(ProfileFormat::Ron);
+        format.deserialize(&std::fs::read(path).map_err(ProfileError::Io)?)
+    }
+}
+
+/// Records the receiver state changes of the [`InputView`] of its entity.
+#[derive(PartialEq, Clone, Debug, Default, Component)]
+pub struct InputRecorder {
+    pub recording: InputRecording,
+    start_tick: Option<u64>,
+    last_time: Option<Duration>,
+}
+
+/// Feeds a recording to the [`InputView`] of its entity.
+#[derive(PartialEq, Clone, Debug, Component)]
+pub struct InputReplay {
+    pub recording: InputRecording,
+    start_tick: Option<u64>,
+    position: usize,
+    /// The replayed time and the clock time of the last replayed tick.
+    time: Option<(Duration, Duration)>,
+}
+
+impl InputReplay {
+    /// Creates a replay starting with the next tick.
+    pub fn new(recording: InputRecording) -> Self {
+        Self {
+            recording,
+            start_tick: None,
+            position: 0,
+            time: None,
+        }
+    }
+
+    /// Returns whether every recorded tick was replayed.
+    pub fn finished(&self) -> bool {
+        self.position >= self.recording.ticks.len()
+    }
+}
+
+impl<Keys> InputView<Keys>
+where
+    Keys: BindingTypeView,
+{
+    /// Remember a receiver state change if this view is being recorded.
+    pub(crate) fn record_change(&mut self, input: InputReceiver, value: f32, pressed: bool) {
+        if let Some(changes) = self.recorded_changes.as_mut() {
+            changes.push(RecordedChange(input, value, pressed));
+        }
+    }
+}
+
+/// Move the state changes of the current tick into the recorder of every recorded view, and stop collecting
+/// changes for views without a recorder.
+pub(crate) fn input_recording_system<Keys>(
+    mut recorded: Query<(&mut InputView<Keys>, &mut InputRecorder)>,
+    mut unrecorded: Query<&mut InputView<Keys>, Without<InputRecorder>>,
+) where
+    Keys: BindingTypeView,
+{
+    for (mut view, mut recorder) in recorded.iter_mut() {
+        let changes = view.recorded_changes.replace(Vec::new());
+        let start_tick = *recorder.start_tick.get_or_insert(view.tick + 1);
+        let last_time = recorder.last_time.replace(view.time).unwrap_or(view.time);
+        if view.tick >= start_tick {
+            let tick = view.tick - start_tick;
+            let delta = view.time.saturating_sub(last_time);
+            let changes = changes.unwrap_or_default();
+            recorder
+                .recording
+                .ticks
+                .push(RecordedTick(tick, delta, changes));
+        }
+    }
+    for mut view in unrecorded.iter_mut() {
+        if view.recorded_changes.is_some() {
+            view.recorded_changes = None;
+        }
+    }
+}
+
+/// Apply the recorded time and state changes of the current tick to every replayed view. Once the recording is
+/// finished, the time of the view keeps advancing with the clock.
+pub(crate) fn input_replay_system<Keys>(mut query: Query<(&mut InputView<Keys>, &mut InputReplay)>)
+where
+    Keys: BindingTypeView,
+{
+    for (mut view, mut replay) in query.iter_mut() {
+        let replay = replay.as_mut();
+        let tick = view.tick - *replay.start_tick.get_or_insert(view.tick);
+        // The replay starts at the current time, and then advances by the recorded time of every tick.
+        let time = match replay.time {
+            Some((time, clock_time)) => match replay.recording.ticks.get(replay.position) {
+                Some(RecordedTick(recorded, delta, _)) if *recorded == tick => time + *delta,
+                _ => time + view.time.saturating_sub(clock_time),
+            },
+            None => view.time,
+        };
+        replay.time = Some((time, view.time));
+        view.time = time;
+        while let Some(RecordedTick(recorded, _, changes)) =
+            replay.recording.ticks.get(replay.position)
+        {
+            if *recorded > tick {
+                break;
+            }
+            for RecordedChange(rcv, value, pressed) in changes.iter().copied() {
+                let state = if pressed {
+                    PressState::Pressed {
+                        started_pressing_instant: None,
+                    }
+                } else {
+                    PressState::Released
+                };
+                view.last_input_source = Some(rcv.source());
+                view.set_axis_value(rcv, value, state);
+            }
+            replay.position += 1;
+        }
+    }
+}
+
+// Test to check if a recorded session is replayed with the same states and times in the same ticks.
+#[test]
+fn input_replay_test() {
+    use crate::harness::{ViewHarness, PRESSED};
+    use bevy::prelude::KeyCode;
+
+    #[derive(crate::BindingTypeView, PartialEq, Eq, Hash, Clone, Copy, Debug)]
+    enum Action {
+        Jump,
+    }
+    let space = InputReceiver::KeyboardKey(KeyCode::Space);
+    let mut view = InputView::new();
+    view.add_binding(&mut ActionBinding::new_from_vec(
+        Action::Jump,
+        vec![vec![space]],
+    ));
+
+    let mut recorder = ViewHarness::new(view.clone(), input_recording_system::<Action>);
+    recorder
+        .world
+        .entity_mut(recorder.entity)
+        .insert(InputRecorder::default());
+    let released = Some(PressState::Released);
+    let presses = [None, Some(PRESSED), None, released, Some(PRESSED)];
+    for (index, press) in presses.iter().enumerate() {
+        let change = press.map(|state| (space, state));
+        recorder.tick(5 << index, change.as_slice());
+    }
+    let recording = recorder
+        .world
+        .get::<InputRecorder>(recorder.entity)
+        .unwrap()
+        .recording
+        .clone();
+    assert_eq!(recording.duration(), 4);
+    let saved = recording.to_string(ProfileFormat::Ron).unwrap();
+    assert!(!saved.contains(char::is_whitespace));
+    let recording: InputRecording = ProfileFormat::Ron.deserialize(saved.as_bytes()).unwrap();
+
+    let mut replayer = ViewHarness::new(view, input_replay_system::<Action>);
+    replayer
+        .world
+        .entity_mut(replayer.entity)
+        .insert(InputReplay::new(recording));
+    let (mut pressed, mut times) = (Vec::new(), Vec::new());
+    for _ in presses.iter().skip(1) {
+        replayer.tick(1000, &[]);
+        pressed.push(replayer.view().key(&Action::Jump).pressed());
+        times.push(replayer.view().time);
+    }
+    assert_eq!(pressed, vec![true, true, false, true]);
+    let millis: Vec<u128> = times
+        .iter()
+        .map(|time| (*time - times[0]).as_millis())
+        .collect();
+    assert_eq!(millis, vec![0, 20, 60, 140]);
+    assert!(replayer
+        .world
+        .get::<InputReplay>(replayer.entity)
+        .unwrap()
+        .finished());
+}
@@ -0,0 +1,215 @@
+//! Sequence bindings, triggering an action when receiver combinations are pressed in order, each one within a
+//! maximum gap from the previous one (e.g. fighting game motion inputs like Down, Down-Forward, Forward+Punch).
+//!
+//! Sequences are evaluated once per tick by the plugin, and a completed sequence presses its action for that
+//! tick, so it can be read with [`InputView::key`] and [`InputView::just_pressed`] like any other action.
+use bevy::{
+    prelude::{Query, ResMut, SystemLabel},
+    utils::Duration,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+
+#[derive(SystemLabel, Clone, Hash, Debug, PartialEq, Eq)]
+pub struct SequenceSystem;
+
+/// Ordered receiver combinations that must be pressed within a maximum gap between steps.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Sequence {
+    pub steps: Vec<InputReceivers>,
+    /// The maximum time, measured by the [`crate::clock::InputClock`], between two consecutive steps.
+    pub max_gap: Duration,
+    #[serde(skip)]
+    progress: usize,
+    #[serde(skip)]
+    last_step: Duration,
+}
+
+impl Sequence {
+    /// Creates a sequence from its steps and the maximum gap between them.
+    pub fn new(steps: Vec<InputReceivers>, max_gap: Duration) -> Self {
+        Self {
+            steps,
+            max_gap,
+            progress: 0,
+            last_step: Duration::ZERO,
+        }
+    }
+
+    /// Returns how many steps were completed so far.
+    pub fn progress(&self) -> usize {
+        self.progress
+    }
+
+    /// Advance this sequence with the state of the given view, returning whether it was completed in the
+    /// current tick. Only one step can be completed per tick.
+    pub fn advance<Keys>(&mut self, view: &InputView<Keys>) -> bool
+    where
+        Keys: BindingTypeView,
+    {
+        let completed = |step: &InputReceivers| {
+            !step.0.is_empty()
+                && step.0.iter().all(|rcv| view.state(rcv).press.pressed())
+                && step
+                    .0
+                    .iter()
+                    .any(|rcv| view.state(rcv).just_pressed_on(view.tick))
+        };
+        if self.progress > 0 && view.time.saturating_sub(self.last_step) > self.max_gap {
+            self.progress = 0;
+        }
+        if self.steps.get(self.progress).is_some_and(completed) {
+            self.progress += 1;
+        } else if self.steps.first().is_some_and(completed) {
+            self.progress = 1;
+        } else {
+            return false;
+        }
+        self.last_step = view.time;
+        if self.progress == self.steps.len() {
+            self.progress = 0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Compare sequences by their steps and maximum gap, ignoring their progress.
+impl PartialEq for Sequence {
+    fn eq(&self, other: &Self) -> bool {
+        self.steps == other.steps && self.max_gap == other.max_gap
+    }
+}
+
+impl<Keys> ActionBinding<Keys>
+where
+    Keys: BindingTypeView,
+{
+    /// Add a sequence triggering this action.
+    pub fn sequence(&mut self, sequence: Sequence) -> &mut Self {
+        self.sequences.push(sequence);
+        self
+    }
+}
+
+impl<Keys> InputView<Keys>
+where
+    Keys: BindingTypeView,
+{
+    /// Returns the state of the sequences of the given key, pressed only in the tick one of them was completed.
+    pub(crate) fn sequence_state(&self, kind: &Keys) -> Option<AxisState> {
+        let completed = *self.completed_sequences.get(kind)?;
+        if completed == self.tick {
+            Some(AxisState {
+                value: 1.,
+                press: PressState::Pressed {
+                    started_pressing_instant: Some(self.time),
+                },
+                pressed_tick: Some(completed),
+                released_tick: None,
+            })
+        } else {
+            Some(AxisState {
+                value: 0.,
+                press: PressState::Released,
+                pressed_tick: Some(completed),
+                released_tick: Some(completed + 1),
+            })
+        }
+    }
+}
+
+/// Advance the sequences of every binding with the input of the current tick.
+pub(crate) fn sequence_system<Keys>(
+    mut query: Query<&mut InputView<Keys>>,
+    global: Option<ResMut<InputView<Keys>>>,
+) where
+    Keys: BindingTypeView,
+{
+    for mut view in query.iter_mut() {
+        advance_sequences(view.as_mut());
+    }
+    if let Some(mut view) = global {
+        advance_sequences(view.as_mut());
+    }
+}
+
+fn advance_sequences<Keys>(view: &mut InputView<Keys>)
+where
+    Keys: BindingTypeView,
+{
+    let mut bindings = std::mem::take(&mut view.bindings);
+    for binding in bindings.values_mut() {
+        for sequence in binding.sequences.iter_mut() {
+            if sequence.advance(view) {
+                view.completed_sequences.insert(binding.key, view.tick);
+            }
+        }
+    }
+    view.bindings = bindings;
+}
+
+// Test to check if a sequence is completed only when its steps are pressed in order and in time, for entities
+// and the global view, and if its progress is ignored when comparing it.
+#[test]
+fn sequence_test() {
+    use crate::harness::{ViewHarness, PRESSED};
+    use bevy::{prelude::KeyCode, utils::Duration};
+
+    #[derive(crate::BindingTypeView, PartialEq, Eq, Hash, Clone, Copy, Debug)]
+    enum Action {
+        Fireball,
+    }
+    let (down, right, punch) = (
+        InputReceiver::KeyboardKey(KeyCode::Down),
+        InputReceiver::KeyboardKey(KeyCode::Right),
+        InputReceiver::KeyboardKey(KeyCode::J),
+    );
+    let fireball = Sequence::new(
+        vec![down.into(), (down, right).into(), (right, punch).into()],
+        Duration::from_millis(300),
+    );
+    let mut view = InputView::new();
+    view.add_binding(ActionBinding::from(Action::Fireball).sequence(fireball.clone()));
+
+    let mut harness = ViewHarness::new(view, sequence_system::<Action>).with_global();
+    let tick = |harness: &mut ViewHarness<Action>,
+                millis: u64,
+                changes: &[(InputReceiver, PressState)]| {
+        harness.tick(millis, changes);
+        let global = harness.world.resource::<InputView<Action>>();
+        assert_eq!(
+            harness.view().just_pressed(&Action::Fireball),
+            global.just_pressed(&Action::Fireball)
+        );
+        global.just_pressed(&Action::Fireball)
+    };
+
+    assert!(!tick(&mut harness, 100, &[(down, PRESSED)]));
+    assert!(!tick(&mut harness, 100, &[(right, PRESSED)]));
+    assert!(!tick(
+        &mut harness,
+        400,
+        &[(down, PressState::Released), (punch, PRESSED)]
+    ));
+    assert!(!tick(
+        &mut harness,
+        100,
+        &[(right, PressState::Released), (punch, PressState::Released)]
+    ));
+
+    assert!(!tick(&mut harness, 100, &[(down, PRESSED)]));
+    assert!(!tick(&mut harness, 100, &[(right, PRESSED)]));
+    let sequence = &harness.view().bindings[&Action::Fireball].sequences[0];
+    assert_eq!((sequence.progress(), sequence), (2, &fireball));
+    assert!(tick(
+        &mut harness,
+        100,
+        &[(down, PressState::Released), (punch, PRESSED)]
+    ));
+    assert!(harness.view().key(&Action::Fireball).pressed());
+    assert!(!tick(&mut harness, 100, &[]));
+    assert!(harness.view().just_released(&Action::Fireball));
+}
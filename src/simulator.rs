@@ -0,0 +1,478 @@
+//! A headless harness for testing control schemes without a window. The [`InputSimulator`] owns an [`App`] with
+//! the minimal plugins, Bevy's input plugin and the [`EZInputPlugin`], and sends the same events a window or a
+//! gamepad would, so the input goes through every system of the plugin.
+//!
+//! The [`InputClock`] of the simulated app is manual, so it only advances with [`InputSimulator::advance`].
+//!
+//! Touch buttons and the touch joystick are simulated with their own fingers, touching the center of their
+//! regions in the first [`TouchMarker`] defining them. Custom receivers are simulated once their source is registered with
+//! [`InputSimulator::simulate_source`].
+use std::marker::PhantomData;
+
+use bevy::{
+    ecs::event::Events,
+    input::{
+        gamepad::GamepadEventRaw,
+        keyboard::KeyboardInput,
+        mouse::{MouseButtonInput, MouseMotion, MouseScrollUnit, MouseWheel},
+        touch::{TouchInput, TouchPhase},
+        ElementState, InputPlugin,
+    },
+    math::Vec2,
+    prelude::{App, Bundle, Entity, Gamepad, GamepadEventType, MinimalPlugins},
+    utils::{Duration, HashMap},
+    window::{CursorMoved, WindowId},
+};
+
+use crate::prelude::*;
+
+/// The finger touching the virtual joystick; the fingers of virtual buttons follow it.
+const JOYSTICK_FINGER: u64 = 1;
+
+/// Sends the simulated event of a custom receiver, returning whether the source can simulate it.
+type CustomSender = fn(&mut App, u32, f32, bool) -> bool;
+
+/// Drives an app with simulated input events, one tick per [`InputSimulator::update`].
+pub struct InputSimulator<Keys>
+where
+    Keys: BindingTypeView,
+{
+    pub app: App,
+    /// The gamepad sending the simulated gamepad events.
+    pub gamepad: Gamepad,
+    cursor: Vec2,
+    touch: Option<Vec2>,
+    joystick: Vec2,
+    custom: HashMap<CustomSource, CustomSender>,
+    phantom_keys: PhantomData<Keys>,
+}
+
+impl<Keys> InputSimulator<Keys>
+where
+    Keys: BindingTypeView,
+{
+    /// Creates a simulator with the default [`EZInputPlugin`].
+    pub fn new() -> Self {
+        Self::with_plugin(EZInputPlugin::default())
+    }
+
+    /// Creates a simulator with the given plugin, e.g. a plugin handling a global view. The first gamepad is
+    /// connected before the first tick.
+    pub fn with_plugin(plugin: EZInputPlugin<Keys>) -> Self {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugin(InputPlugin)
+            .add_event::<CursorMoved>()
+            .insert_resource(InputClock::manual())
+            .add_plugin(plugin);
+        let mut simulator = Self {
+            app,
+            gamepad: Gamepad(0),
+            cursor: Vec2::ZERO,
+            touch: None,
+            joystick: Vec2::ZERO,
+            custom: HashMap::default(),
+            phantom_keys: PhantomData,
+        };
+        simulator.send_event(GamepadEventRaw(
+            simulator.gamepad,
+            GamepadEventType::Connected,
+        ));
+        simulator
+    }
+
+    /// Spawn an entity with the given view and every built-in input marker.
+    pub fn spawn(&mut self, view: InputView<Keys>) -> Entity {
+        self.spawn_bundle(InputHandlingBundle::new(view))
+    }
+
+    /// Spawn an entity with the given bundle.
+    pub fn spawn_bundle(&mut self, bundle: impl Bundle) -> Entity {
+        self.app.world.spawn().insert_bundle(bundle).id()
+    }
+
+    /// Send an event, handled in the next tick. Useful for the events of a [`CustomInputSource`].
+    pub fn send_event<E>(&mut self, event: E)
+    where
+        E: Send + Sync + 'static,
+    {
+        self.app.world.resource_mut::<Events<E>>().send(event);
+    }
+
+    /// Let the receivers of the given custom source be simulated with the events returned by
+    /// [`CustomInputSource::simulated_event`].
+    pub fn simulate_source<Source>(&mut self) -> &mut Self
+    where
+        Source: CustomInputSource,
+    {
+        fn send<Source: CustomInputSource>(
+            app: &mut App,
+            rcv: u32,
+            value: f32,
+            pressed: bool,
+        ) -> bool {
+            match Source::simulated_event(rcv, value, pressed) {
+                Some(event) => {
+                    app.world
+                        .resource_mut::<Events<Source::Event>>()
+                        .send(event);
+                    true
+                }
+                None => false,
+            }
+        }
+        self.custom.insert(Source::SOURCE, send::<Source>);
+        self
+    }
+
+    /// Press the given receiver in the next tick.
+    ///
+    /// # Panics
+    ///
+    /// Panics for touch buttons and joysticks without a [`TouchMarker`] defining them, and for custom receivers
+    /// whose source isn't registered with [`InputSimulator::simulate_source`] or can't simulate them.
+    pub fn press(&mut self, receiver: InputReceiver) {
+        self.send_receiver(receiver, 1., true);
+    }
+
+    /// Release the given receiver in the next tick.
+    ///
+    /// # Panics
+    ///
+    /// Panics in the same cases as [`InputSimulator::press`].
+    pub fn release(&mut self, receiver: InputReceiver) {
+        self.send_receiver(receiver, 0., false);
+    }
+
+    /// Move the given axis receiver to a value in the next tick. Mouse, touch and joystick positions are set per
+    /// axis, so the other axis keeps its last value, and deltas move them by the value.
+    ///
+    /// # Panics
+    ///
+    /// Panics in the same cases as [`InputSimulator::press`].
+    pub fn set_axis(&mut self, receiver: InputReceiver, value: f32) {
+        self.send_receiver(receiver, value, value != 0.);
+    }
+
+    /// Move the cursor to the given position, sending its motion along with it like a window does.
+    pub fn move_cursor(&mut self, position: Vec2) {
+        let delta = position - self.cursor;
+        self.cursor = position;
+        self.send_event(CursorMoved {
+            id: WindowId::primary(),
+            position,
+        });
+        self.send_event(MouseMotion { delta });
+    }
+
+    /// Touch the screen with the primary finger at the given position, or move the finger there if it is
+    /// already touching the screen.
+    pub fn touch(&mut self, position: Vec2) {
+        let phase = match self.touch {
+            Some(_) => TouchPhase::Moved,
+            None => TouchPhase::Started,
+        };
+        self.touch = Some(position);
+        self.send_touch(phase, position);
+    }
+
+    /// Lift the primary finger from the screen.
+    pub fn lift(&mut self) {
+        if let Some(position) = self.touch.take() {
+            self.send_touch(TouchPhase::Ended, position);
+        }
+    }
+
+    /// Run a single tick of the app.
+    pub fn update(&mut self) {
+        self.app.update();
+    }
+
+    /// Advance the [`InputClock`] by the given duration and run a single tick of the app.
+    pub fn advance(&mut self, delta: Duration) {
+        self.app.world.resource_mut::<InputClock>().advance(delta);
+        self.update();
+    }
+
+    /// Returns the view of the given entity.
+    pub fn view(&self, entity: Entity) -> &InputView<Keys> {
+        self.app
+            .world
+            .get::<InputView<Keys>>(entity)
+            .expect("the entity has no input view")
+    }
+
+    /// Panics unless the action for the given key is pressed for the given entity.
+    pub fn assert_pressed(&self, entity: Entity, kind: &Keys) {
+        assert!(
+            self.view(entity).key(kind).pressed(),
+            "expected {:?} to be pressed",
+            kind
+        );
+    }
+
+    /// Panics unless the action for the given key is released for the given entity.
+    pub fn assert_released(&self, entity: Entity, kind: &Keys) {
+        assert!(
+            self.view(entity).key(kind).released(),
+            "expected {:?} to be released",
+            kind
+        );
+    }
+
+    /// Send the event setting the given receiver. Touch buttons and joysticks are pressed by touching their
+    /// regions, and custom receivers by sending the events of their source.
+    fn send_receiver(&mut self, receiver: InputReceiver, value: f32, pressed: bool) {
+        let state = if pressed {
+            ElementState::Pressed
+        } else {
+            ElementState::Released
+        };
+        match receiver {
+            InputReceiver::KeyboardKey(key_code) => self.send_event(KeyboardInput {
+                scan_code: 0,
+                key_code: Some(key_code),
+                state,
+            }),
+            InputReceiver::MouseButton(button) => {
+                self.send_event(MouseButtonInput { button, state })
+            }
+            InputReceiver::MouseAxis(MouseAxisType::Wheel)
+            | InputReceiver::MouseAxisDelta(MouseAxisType::Wheel) => self.send_event(MouseWheel {
+                unit: MouseScrollUnit::Line,
+                x: 0.,
+                y: value,
+            }),
+            InputReceiver::MouseAxis(axis) => {
+                let mut position = self.cursor;
+                match axis {
+                    MouseAxisType::X => position.x = value,
+                    _ => position.y = value,
+                }
+                self.move_cursor(position);
+            }
+            InputReceiver::MouseAxisDelta(axis) => {
+                let mut position = self.cursor;
+                match axis {
+                    MouseAxisType::X => position.x += value,
+                    _ => position.y += value,
+                }
+                self.move_cursor(position);
+            }
+            InputReceiver::GamepadButton(button) => self.send_event(GamepadEventRaw(
+                self.gamepad,
+                GamepadEventType::ButtonChanged(button, value),
+            )),
+            InputReceiver::GamepadAxis(axis) => self.send_event(GamepadEventRaw(
+                self.gamepad,
+                GamepadEventType::AxisChanged(axis, value),
+            )),
+            InputReceiver::TouchPress if pressed => self.touch(self.touch.unwrap_or_default()),
+            InputReceiver::TouchPress => self.lift(),
+            InputReceiver::TouchAxis(axis) => {
+                let mut position = self.touch.unwrap_or_default();
+                match axis {
+                    TouchAxisType::X => position.x = value,
+                    TouchAxisType::Y => position.y = value,
+                }
+                self.touch(position);
+            }
+            InputReceiver::TouchAxisDelta(axis) => {
+                let mut position = self.touch.unwrap_or_default();
+                match axis {
+                    TouchAxisType::X => position.x += value,
+                    TouchAxisType::Y => position.y += value,
+                }
+                self.touch(position);
+            }
+            InputReceiver::TouchButton(index) => {
+                let button = self
+                    .find_touch_marker(|marker| marker.buttons.get(index as usize).copied())
+                    .unwrap_or_else(|| panic!("{:?} isn't defined by a touch marker", receiver));
+                let finger = JOYSTICK_FINGER + 1 + index as u64;
+                let phase = if pressed {
+                    TouchPhase::Started
+                } else {
+                    TouchPhase::Ended
+                };
+                self.send_finger(finger, phase, (button.min + button.max) / 2.);
+            }
+            InputReceiver::TouchJoystick(axis) => {
+                let joystick = self
+                    .find_touch_marker(|marker| marker.joystick)
+                    .unwrap_or_else(|| panic!("{:?} isn't defined by a touch marker", receiver));
+                let center = (joystick.min + joystick.max) / 2.;
+                let touching = self.joystick != Vec2::ZERO;
+                match axis {
+                    TouchAxisType::X => self.joystick.x = value,
+                    TouchAxisType::Y => self.joystick.y = value,
+                }
+                if self.joystick == Vec2::ZERO {
+                    self.send_finger(JOYSTICK_FINGER, TouchPhase::Ended, center);
+                } else {
+                    if !touching {
+                        self.send_finger(JOYSTICK_FINGER, TouchPhase::Started, center);
+                    }
+                    let position = center + self.joystick * joystick.radius;
+                    self.send_finger(JOYSTICK_FINGER, TouchPhase::Moved, position);
+                }
+            }
+            InputReceiver::Custom(source, rcv) => {
+                let send = self.custom.get(&source).copied();
+                if !send.is_some_and(|send| send(&mut self.app, rcv, value, pressed)) {
+                    panic!("{:?} can't be simulated by its source", receiver);
+                }
+            }
+        }
+    }
+
+    /// Returns the first value found in the touch markers of the app, e.g. a virtual button.
+    fn find_touch_marker<T>(&mut self, find: impl Fn(&TouchMarker) -> Option<T>) -> Option<T> {
+        let mut query = self.app.world.query::<&TouchMarker>();
+        query.iter(&self.app.world).find_map(find)
+    }
+
+    fn send_touch(&mut self, phase: TouchPhase, position: Vec2) {
+        self.send_finger(0, phase, position);
+    }
+
+    fn send_finger(&mut self, id: u64, phase: TouchPhase, position: Vec2) {
+        self.send_event(TouchInput {
+            phase,
+            position,
+            force: None,
+            id,
+        });
+    }
+}
+
+impl<Keys> Default for InputSimulator<Keys>
+where
+    Keys: BindingTypeView,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Test to check if simulated events go through the plugin and update the actions of a spawned view, including
+// touch regions and custom sources.
+#[test]
+fn input_simulator_test() {
+    use bevy::prelude::{Component, GamepadButtonType, KeyCode};
+
+    #[derive(crate::BindingTypeView, PartialEq, Eq, Hash, Clone, Copy, Debug)]
+    enum Action {
+        Jump,
+        Look,
+        Move,
+    }
+    struct PedalEvent(bool);
+    #[derive(Component)]
+    struct PedalMarker;
+    impl CustomInputSource for PedalMarker {
+        const SOURCE: CustomSource = CustomSource(3);
+        type Receiver = u32;
+        type Event = PedalEvent;
+
+        fn handle_event<Keys>(&mut self, view: &mut InputView<Keys>, event: &PedalEvent)
+        where
+            Keys: BindingTypeView,
+        {
+            let state = if event.0 {
+                PressState::Pressed {
+                    started_pressing_instant: None,
+                }
+            } else {
+                PressState::Released
+            };
+            self.set_receiver_state(view, 0, state);
+        }
+
+        fn simulated_event(_receiver: u32, _value: f32, pressed: bool) -> Option<PedalEvent> {
+            Some(PedalEvent(pressed))
+        }
+    }
+    let mut view = InputView::new();
+    view.add_binding(&mut ActionBinding::new_from_vec(
+        Action::Jump,
+        vec![
+            vec![KeyCode::Space.into()],
+            vec![GamepadButtonType::South.into()],
+        ],
+    ));
+    view.add_binding(&mut ActionBinding::new_from_vec(
+        Action::Look,
+        vec![vec![InputReceiver::MouseAxisDelta(MouseAxisType::X)]],
+    ));
+
+    let mut simulator = InputSimulator::<Action>::new();
+    let entity = simulator.spawn(view);
+    simulator.update();
+    simulator.assert_released(entity, &Action::Jump);
+
+    simulator.press(KeyCode::Space.into());
+    simulator.update();
+    simulator.assert_pressed(entity, &Action::Jump);
+    assert!(simulator.view(entity).just_pressed(&Action::Jump));
+    simulator.advance(Duration::from_millis(500));
+    assert!(simulator
+        .view(entity)
+        .is_pressed_for(&Action::Jump, Duration::from_millis(500)));
+    simulator.release(KeyCode::Space.into());
+    simulator.update();
+    simulator.assert_released(entity, &Action::Jump);
+
+    simulator.press(GamepadButtonType::South.into());
+    simulator.set_axis(InputReceiver::MouseAxisDelta(MouseAxisType::X), 4.);
+    simulator.update();
+    simulator.assert_pressed(entity, &Action::Jump);
+    let view = simulator.view(entity);
+    assert_eq!(view.axis(&Action::Look)[0].value, 4.);
+    simulator.release(GamepadButtonType::South.into());
+
+    let mut view = InputView::new();
+    view.add_binding(&mut ActionBinding::new_from_vec(
+        Action::Jump,
+        vec![
+            vec![InputReceiver::TouchButton(0)],
+            vec![PedalMarker::receiver(0)],
+        ],
+    ));
+    view.add_binding(ActionBinding::from(Action::Move).dual_axis(DualAxis::touch_joystick()));
+    let touch = TouchMarker::default()
+        .with_button(VirtualButton::new(Vec2::ZERO, Vec2::splat(100.)))
+        .with_joystick(VirtualJoystick::new(
+            Vec2::new(200., 0.),
+            Vec2::new(400., 200.),
+            50.,
+        ));
+    let entity = simulator.spawn_bundle(InputHandlingBundle {
+        touch_input: touch,
+        ..InputHandlingBundle::new(view)
+    });
+    simulator.app.world.entity_mut(entity).insert(PedalMarker);
+    simulator
+        .app
+        .add_plugin(CustomInputPlugin::<Action, PedalMarker>::default());
+    simulator.simulate_source::<PedalMarker>();
+    simulator.update();
+
+    simulator.press(InputReceiver::TouchButton(0));
+    simulator.set_axis(InputReceiver::TouchJoystick(TouchAxisType::X), 0.5);
+    simulator.update();
+    simulator.assert_pressed(entity, &Action::Jump);
+    assert_eq!(
+        simulator.view(entity).axis_pair(&Action::Move),
+        Vec2::new(0.5, 0.)
+    );
+    simulator.release(InputReceiver::TouchButton(0));
+    simulator.set_axis(InputReceiver::TouchJoystick(TouchAxisType::X), 0.);
+    simulator.update();
+    simulator.assert_released(entity, &Action::Jump);
+    assert_eq!(simulator.view(entity).axis_pair(&Action::Move), Vec2::ZERO);
+
+    simulator.press(PedalMarker::receiver(0));
+    simulator.update();
+    simulator.assert_pressed(entity, &Action::Jump);
+}
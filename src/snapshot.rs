@@ -0,0 +1,434 @@
+//! Compact per-tick snapshots of the actions of an [`InputView`], for sending input over the network and
+//! rolling it back.
+//!
+//! A snapshot stores whether each action is pressed as a single bit, and the axis value of every pressed action
+//! quantized to 16 bits in the `[-1, 1]` range. Actions with [`DualAxis`] sources also store whether their 2D axis
+//! value is moved as another bit, followed by the value of each moved one. Values outside of the `[-1, 1]` range,
+//! such as mouse deltas, are clamped, so they need to be scaled into it with an [`AxisProcessor`] to be
+//! snapshotted. Actions are stored in the order of [`BindingTypeView::all`], so the layout doesn't depend on the
+//! binding order, pushed contexts or rebinding. Keys without [`BindingTypeView::all`] fall back to the bound
+//! actions sorted by their debug representation, so both views must bind the same actions. A view with an applied
+//! snapshot reports the state of its actions from the snapshot instead of its receivers until
+//! [`InputView::clear_snapshot`] is called.
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt::Display,
+};
+
+use bevy::{
+    math::Vec2,
+    prelude::{Component, Query, SystemLabel},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+
+#[derive(SystemLabel, Clone, Hash, Debug, PartialEq, Eq)]
+pub struct InputSnapshotSystem;
+
+/// The version of the snapshot layout, checked when applying a snapshot.
+pub const SNAPSHOT_VERSION: u8 = 2;
+
+/// The state of every action of a view in a single tick.
+#[derive(PartialEq, Eq, Clone, Debug, Deserialize, Serialize)]
+pub struct InputSnapshot {
+    /// The layout version this snapshot was captured with.
+    pub version: u8,
+    pub tick: u64,
+    /// The number of actions stored in this snapshot.
+    pub actions: u32,
+    /// Whether each action is pressed, eight actions per byte.
+    pub pressed: Vec<u8>,
+    /// Whether the 2D axis value of each action is moved, eight actions per byte.
+    pub dual: Vec<u8>,
+    /// The quantized axis value of each pressed action, followed by the 2D axis value of each moved one.
+    pub axes: Vec<i16>,
+}
+
+impl Default for InputSnapshot {
+    fn default() -> Self {
+        Self {
+            version: SNAPSHOT_VERSION,
+            tick: 0,
+            actions: 0,
+            pressed: Vec::new(),
+            dual: Vec::new(),
+            axes: Vec::new(),
+        }
+    }
+}
+
+impl InputSnapshot {
+    /// Capture the state of the actions of the given view in its current tick.
+    pub fn capture<Keys>(view: &InputView<Keys>) -> Self
+    where
+        Keys: BindingTypeView,
+    {
+        let order = snapshot_order(view);
+        let mut snapshot = Self {
+            tick: view.tick,
+            actions: order.len() as u32,
+            pressed: vec![0; order.len().div_ceil(8)],
+            dual: vec![0; order.len().div_ceil(8)],
+            ..Default::default()
+        };
+        for (index, key) in order.iter().enumerate() {
+            // The action value is the value of the last receiver of the pressed combination.
+            let state = view.axis(key).last().copied().unwrap_or(AxisState::ZERO);
+            if state.press.pressed() {
+                snapshot.pressed[index / 8] |= 1 << (index % 8);
+                snapshot.axes.push(quantize(state.value));
+            }
+            let pair = view.axis_pair(key);
+            if pair != Vec2::ZERO {
+                snapshot.dual[index / 8] |= 1 << (index % 8);
+                snapshot.axes.extend([quantize(pair.x), quantize(pair.y)]);
+            }
+        }
+        snapshot
+    }
+
+    /// Returns whether the action at the given index is pressed.
+    pub fn is_pressed(&self, index: usize) -> bool {
+        is_set(&self.pressed, index)
+    }
+
+    /// Returns whether the 2D axis value of the action at the given index is moved.
+    pub fn is_dual(&self, index: usize) -> bool {
+        is_set(&self.dual, index)
+    }
+
+    /// Returns an error unless this snapshot has the current layout version and stores the given number of
+    /// actions, with an axis value for each pressed one and a 2D axis value for each moved one.
+    fn validate(&self, actions: usize) -> Result<(), SnapshotError> {
+        if self.version != SNAPSHOT_VERSION {
+            return Err(SnapshotError::Version(self.version));
+        }
+        let axes: usize = (0..actions)
+            .map(|index| self.is_pressed(index) as usize + 2 * self.is_dual(index) as usize)
+            .sum();
+        if self.actions as usize != actions
+            || self.pressed.len() != actions.div_ceil(8)
+            || self.dual.len() != actions.div_ceil(8)
+            || self.axes.len() != axes
+        {
+            return Err(SnapshotError::Length);
+        }
+        Ok(())
+    }
+}
+
+/// An error that happened while applying a snapshot.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum SnapshotError {
+    /// The snapshot was captured with another layout version.
+    Version(u8),
+    /// The snapshot doesn't store the actions of the view.
+    Length,
+}
+
+impl Display for SnapshotError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SnapshotError::Version(version) => write!(
+                f,
+                "Snapshot layout version {} doesn't match version {}",
+                version, SNAPSHOT_VERSION
+            ),
+            SnapshotError::Length => write!(f, "Snapshot doesn't match the actions of the view"),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+/// Returns the actions stored in the snapshots of the given view, in a stable order.
+fn snapshot_order<Keys>(view: &InputView<Keys>) -> Vec<Keys>
+where
+    Keys: BindingTypeView,
+{
+    let all = Keys::all();
+    if !all.is_empty() {
+        return all;
+    }
+    let mut keys: Vec<Keys> = view.bindings.keys().copied().collect();
+    keys.sort_by_cached_key(|key| format!("{:?}", key));
+    keys
+}
+
+#[inline]
+fn is_set(bits: &[u8], index: usize) -> bool {
+    bits.get(index / 8)
+        .is_some_and(|byte| byte & (1 << (index % 8)) != 0)
+}
+
+/// Quantize an axis value to 16 bits, clamping it to the `[-1, 1]` range, so larger values such as mouse deltas
+/// need to be scaled by the processors of their binding first.
+fn quantize(value: f32) -> i16 {
+    (value.clamp(-1., 1.) * i16::MAX as f32).round() as i16
+}
+
+fn dequantize(value: i16) -> f32 {
+    (value as f32 / i16::MAX as f32).max(-1.)
+}
+
+/// A ring buffer of the latest snapshots of the view of its entity, filled once per tick by the plugin.
+#[derive(PartialEq, Eq, Clone, Debug, Component)]
+pub struct SnapshotBuffer {
+    capacity: usize,
+    snapshots: VecDeque<InputSnapshot>,
+}
+
+impl SnapshotBuffer {
+    /// Creates an empty buffer keeping the given number of snapshots.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            snapshots: VecDeque::with_capacity(capacity.max(1)),
+        }
+    }
+
+    /// Returns the number of snapshots kept by this buffer.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Add a snapshot, replacing the snapshot of the same tick and dropping the oldest one if the buffer is full.
+    /// Snapshots are kept sorted by tick.
+    pub fn push(&mut self, snapshot: InputSnapshot) {
+        match self
+            .snapshots
+            .binary_search_by_key(&snapshot.tick, |other| other.tick)
+        {
+            Ok(index) => self.snapshots[index] = snapshot,
+            Err(index) => {
+                self.snapshots.insert(index, snapshot);
+                if self.snapshots.len() > self.capacity {
+                    self.snapshots.pop_front();
+                }
+            }
+        }
+    }
+
+    /// Returns the snapshot of the given tick.
+    pub fn get(&self, tick: u64) -> Option<&InputSnapshot> {
+        self.snapshots
+            .binary_search_by_key(&tick, |snapshot| snapshot.tick)
+            .ok()
+            .map(|index| &self.snapshots[index])
+    }
+
+    /// Returns the latest snapshot.
+    pub fn latest(&self) -> Option<&InputSnapshot> {
+        self.snapshots.back()
+    }
+
+    /// Returns every snapshot from the given tick onwards, e.g. to resimulate them after a rollback.
+    pub fn since(&self, tick: u64) -> impl Iterator<Item = &InputSnapshot> {
+        self.snapshots
+            .iter()
+            .filter(move |snapshot| snapshot.tick >= tick)
+    }
+
+    /// Remove every snapshot after the given tick, e.g. predicted snapshots replaced by the confirmed ones.
+    pub fn truncate_after(&mut self, tick: u64) {
+        self.snapshots.retain(|snapshot| snapshot.tick <= tick);
+    }
+}
+
+/// The action states and 2D axis values of an applied snapshot.
+pub(crate) type SnapshotStates<Keys> = HashMap<Keys, (AxisState, Vec2)>;
+
+impl<Keys> InputView<Keys>
+where
+    Keys: BindingTypeView,
+{
+    /// Apply a snapshot captured from another view, so the actions of this view report its state. Press and
+    /// release edges are computed against the previously applied snapshot, in the current tick of this view.
+    /// Snapshots with another layout version or other actions are rejected.
+    pub fn apply_snapshot(&mut self, snapshot: &InputSnapshot) -> Result<(), SnapshotError> {
+        let order = snapshot_order(self);
+        snapshot.validate(order.len())?;
+        let tick = self.tick;
+        let mut states = self.snapshot_states.take().unwrap_or_default();
+        let mut axes = snapshot.axes.iter().copied().map(dequantize);
+        for (index, key) in order.iter().enumerate() {
+            let (state, pair) = states.entry(*key).or_insert((AxisState::ZERO, Vec2::ZERO));
+            if snapshot.is_pressed(index) {
+                let value = axes.next().unwrap_or_default();
+                let press = PressState::Pressed {
+                    started_pressing_instant: Some(self.time),
+                };
+                state.update(value, press, tick);
+            } else {
+                state.update(0., PressState::Released, tick);
+            }
+            *pair = if snapshot.is_dual(index) {
+                Vec2::new(
+                    axes.next().unwrap_or_default(),
+                    axes.next().unwrap_or_default(),
+                )
+            } else {
+                Vec2::ZERO
+            };
+        }
+        self.snapshot_states = Some(states);
+        Ok(())
+    }
+
+    /// Stop reporting the state of an applied snapshot, returning to the receivers of this view.
+    pub fn clear_snapshot(&mut self) {
+        self.snapshot_states = None;
+    }
+
+    /// Returns whether this view reports the state of an applied snapshot.
+    pub fn has_snapshot(&self) -> bool {
+        self.snapshot_states.is_some()
+    }
+
+    /// Returns the state of the given key from the applied snapshot, if any.
+    pub(crate) fn snapshot_state(&self, kind: &Keys) -> Option<AxisState> {
+        let states = self.snapshot_states.as_ref()?;
+        Some(
+            states
+                .get(kind)
+                .map_or(AxisState::ZERO, |(state, _)| *state),
+        )
+    }
+
+    /// Returns the 2D axis value of the given key from the applied snapshot, if any.
+    pub(crate) fn snapshot_pair(&self, kind: &Keys) -> Option<Vec2> {
+        let states = self.snapshot_states.as_ref()?;
+        Some(states.get(kind).map_or(Vec2::ZERO, |(_, pair)| *pair))
+    }
+}
+
+/// Capture a snapshot of every view with a [`SnapshotBuffer`].
+pub(crate) fn input_snapshot_system<Keys>(mut query: Query<(&InputView<Keys>, &mut SnapshotBuffer)>)
+where
+    Keys: BindingTypeView,
+{
+    for (view, mut buffer) in query.iter_mut() {
+        buffer.push(InputSnapshot::capture(view));
+    }
+}
+
+// Test to check if a snapshot is packed, serialized and applied to another view with the same action states and
+// values, regardless of the binding order, and if snapshots with another layout are rejected.
+#[test]
+fn input_snapshot_test() {
+    use bevy::prelude::{GamepadAxisType, GamepadButtonType, KeyCode};
+
+    #[derive(crate::BindingTypeView, PartialEq, Eq, Hash, Clone, Copy, Debug)]
+    enum Action {
+        Jump,
+        Crouch,
+        Steer,
+        Move,
+        Fire,
+    }
+    let (space, stick, bumper, trigger) = (
+        InputReceiver::KeyboardKey(KeyCode::Space),
+        InputReceiver::GamepadAxis(GamepadAxisType::LeftStickX),
+        InputReceiver::GamepadButton(GamepadButtonType::LeftTrigger),
+        InputReceiver::GamepadButton(GamepadButtonType::RightTrigger2),
+    );
+    let pressed = PressState::Pressed {
+        started_pressing_instant: None,
+    };
+    let view = |order: &[Action]| {
+        let mut view = InputView::new();
+        for action in order {
+            let mut binding = match action {
+                Action::Jump => ActionBinding::new_from_vec(*action, vec![vec![space]]),
+                Action::Crouch => {
+                    ActionBinding::new_from_vec(*action, vec![vec![KeyCode::C.into()]])
+                }
+                Action::Steer => ActionBinding::new_from_vec(*action, vec![vec![stick]]),
+                Action::Move => ActionBinding::from(*action),
+                Action::Fire => ActionBinding::new_from_vec(*action, vec![vec![bumper, trigger]]),
+            };
+            if *action == Action::Move {
+                binding.dual_axis(DualAxis::wasd());
+            }
+            view.add_binding(&mut binding);
+        }
+        view
+    };
+    let mut local = view(&[
+        Action::Jump,
+        Action::Crouch,
+        Action::Steer,
+        Action::Move,
+        Action::Fire,
+    ]);
+    let mut remote = view(&[
+        Action::Fire,
+        Action::Move,
+        Action::Steer,
+        Action::Crouch,
+        Action::Jump,
+    ]);
+
+    local.tick = 1;
+    local.set_key_receiver_state(space, pressed);
+    local.set_axis_value(stick, -0.5, pressed);
+    local.set_key_receiver_state(KeyCode::D.into(), pressed);
+    local.set_axis_value(bumper, 1., pressed);
+    local.set_axis_value(trigger, 0.25, pressed);
+    assert!(local.key(&Action::Move).released());
+    let snapshot = InputSnapshot::capture(&local);
+    assert_eq!(snapshot.pressed, vec![0b10101]);
+    assert_eq!(snapshot.dual, vec![0b1000]);
+    let json = serde_json::to_string(&snapshot).unwrap();
+    let snapshot: InputSnapshot = serde_json::from_str(&json).unwrap();
+
+    remote.tick = 7;
+    remote.apply_snapshot(&snapshot).unwrap();
+    assert!(remote.just_pressed(&Action::Jump));
+    assert!(remote.key(&Action::Crouch).released());
+    assert!((remote.axis(&Action::Steer)[0].value + 0.5).abs() < 1e-4);
+    assert_eq!(remote.axis_pair(&Action::Move), Vec2::new(1., 0.));
+    assert!(remote.key(&Action::Move).released());
+    assert!((remote.axis(&Action::Fire)[0].value - 0.25).abs() < 1e-4);
+    remote.tick = 8;
+    let released = InputSnapshot {
+        actions: 5,
+        pressed: vec![0],
+        dual: vec![0],
+        ..Default::default()
+    };
+    remote.apply_snapshot(&released).unwrap();
+    assert!(remote.just_released(&Action::Jump));
+    assert_eq!(remote.axis_pair(&Action::Move), Vec2::ZERO);
+
+    let outdated = InputSnapshot {
+        version: 0,
+        ..snapshot.clone()
+    };
+    assert_eq!(
+        remote.apply_snapshot(&outdated),
+        Err(SnapshotError::Version(0))
+    );
+    let truncated = InputSnapshot {
+        axes: snapshot.axes[1..].to_vec(),
+        ..snapshot
+    };
+    assert_eq!(
+        remote.apply_snapshot(&truncated),
+        Err(SnapshotError::Length)
+    );
+
+    let mut buffer = SnapshotBuffer::new(2);
+    for tick in [3, 1, 2] {
+        buffer.push(InputSnapshot {
+            tick,
+            ..Default::default()
+        });
+    }
+    assert!(buffer.get(1).is_none());
+    assert_eq!(
+        buffer.since(0).map(|s| s.tick).collect::<Vec<_>>(),
+        vec![2, 3]
+    );
+}
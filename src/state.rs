@@ -1,21 +1,18 @@
 //! The press state for a button or axis. Also useful methods for checking the elapsed time.
 use std::fmt::{Debug, Display};
 
-#[allow(unused_imports)]
-use std::ops::Add;
-
 use bevy::input::ElementState as ButtonState;
-use bevy::utils::{Duration, Instant};
+use bevy::utils::Duration;
 
 /// The current state of a specific axis or button. By default, calls return [`PressState::Released`].
 #[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
 pub enum PressState {
-    /// The button or axis is pressed, along with the initial instant for the press.
-    /// This need to be set as none if is the moment the button is just pressed, since it will
+    /// The button or axis is pressed, along with the [`crate::clock::InputClock`] time in which the press
+    /// started. This need to be set as none if is the moment the button is just pressed, since it will
     /// let the input view know that the button is just pressed. The pressing instant is set
-    /// in the next tick to allow users to know the pressing duration.
+    /// in the next tick to the clock time of the tick in which the press happened.
     Pressed {
-        started_pressing_instant: Option<Instant>,
+        started_pressing_instant: Option<Duration>,
     },
 
     /// The button or axis is released.
@@ -26,17 +23,20 @@ pub trait PressStateExt {
     /// Returns whether if the current press state is released or not.
     fn released(&self) -> bool;
 
-    /// Returns whether if the current press state is pressed for more than a specific duration.
-    fn is_pressed_for(&self, duration: Duration) -> bool;
+    /// Returns whether if the current press state is pressed for more than a specific duration at the given
+    /// clock time.
+    fn is_pressed_for(&self, duration: Duration, now: Duration) -> bool;
 
-    /// Returns whether the button or axis was just pressed or moved in this exact tick or not.
+    /// Returns whether the button or axis was pressed and its pressing instant wasn't set by a tick yet.
+    ///
+    /// Prefer [`crate::view::InputView::just_pressed`] for a frame-accurate answer.
     fn just_pressed(&self) -> bool;
 
     /// Returns whether the button or axis is currently pressed or moving.
     fn pressed(&self) -> bool;
 
-    /// Returns the elapsed time since the action was pressed.
-    fn elapsed(&self) -> Option<Duration>;
+    /// Returns the elapsed time since the action was pressed at the given clock time.
+    fn elapsed(&self, now: Duration) -> Option<Duration>;
 }
 
 impl PressStateExt for PressState {
@@ -46,13 +46,12 @@ impl PressStateExt for PressState {
     }
 
     #[inline]
-    fn is_pressed_for(&self, duration: Duration) -> bool {
+    fn is_pressed_for(&self, duration: Duration, now: Duration) -> bool {
         if let PressState::Pressed {
-            started_pressing_instant,
+            started_pressing_instant: Some(instant),
         } = *self
         {
-            started_pressing_instant.is_some()
-                && started_pressing_instant.unwrap().elapsed() >= duration
+            now.saturating_sub(instant) >= duration
         } else {
             false
         }
@@ -60,18 +59,12 @@ impl PressStateExt for PressState {
 
     #[inline]
     fn just_pressed(&self) -> bool {
-        if let PressState::Pressed {
-            started_pressing_instant,
-        } = *self
-        {
-            if let Some(instant) = started_pressing_instant {
-                instant.elapsed().as_millis() <= 1
-            } else {
-                true
+        matches!(
+            *self,
+            PressState::Pressed {
+                started_pressing_instant: None
             }
-        } else {
-            false
-        }
+        )
     }
 
     #[inline]
@@ -80,13 +73,12 @@ impl PressStateExt for PressState {
     }
 
     #[inline]
-    fn elapsed(&self) -> Option<Duration> {
+    fn elapsed(&self, now: Duration) -> Option<Duration> {
         match self {
             PressState::Pressed {
                 started_pressing_instant,
             } => started_pressing_instant
-                .as_ref()
-                .map(|started_pressing_instant| started_pressing_instant.elapsed())
+                .map(|started_pressing_instant| now.saturating_sub(started_pressing_instant))
                 .or(Some(Duration::ZERO)),
             _ => None,
         }
@@ -96,30 +88,30 @@ impl PressStateExt for PressState {
 /// Implement partial comparision between press states.
 impl PartialOrd for PressState {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Implement comparison between press states.
+impl Ord for PressState {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
         match self {
             PressState::Pressed {
                 started_pressing_instant: a,
             } => match other {
                 PressState::Pressed {
                     started_pressing_instant: b,
-                } => Some(a.cmp(b)),
-                PressState::Released => Some(std::cmp::Ordering::Greater),
+                } => a.cmp(b),
+                PressState::Released => std::cmp::Ordering::Greater,
             },
             PressState::Released => match other {
-                PressState::Pressed { .. } => Some(std::cmp::Ordering::Less),
-                PressState::Released => Some(std::cmp::Ordering::Equal),
+                PressState::Pressed { .. } => std::cmp::Ordering::Less,
+                PressState::Released => std::cmp::Ordering::Equal,
             },
         }
     }
 }
 
-/// Implement comparison between press states.
-impl Ord for PressState {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.partial_cmp(other).unwrap()
-    }
-}
-
 /// Implementation responsible for translating Bevy element states to EZInput press states.
 /// By default, the default pressing instant is the None.
 impl From<ButtonState> for PressState {
@@ -137,13 +129,12 @@ impl From<ButtonState> for PressState {
 impl Display for PressState {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match *self {
-            PressState::Pressed { .. } => {
-                if self.just_pressed() {
-                    write!(f, "Pressing since Now")
-                } else {
-                    write!(f, "Pressing for {:?}", self.elapsed())
-                }
-            }
+            PressState::Pressed {
+                started_pressing_instant,
+            } => match started_pressing_instant {
+                Some(instant) => write!(f, "Pressing since {:?}", instant),
+                None => write!(f, "Pressing since Now"),
+            },
 
             PressState::Released => write!(f, "Released"),
         }
@@ -154,36 +145,82 @@ impl Display for PressState {
 #[test]
 fn partial_ord_press_state_test() {
     let a = PressState::Pressed {
-        started_pressing_instant: Some(Instant::now()),
+        started_pressing_instant: Some(Duration::from_secs(1)),
     };
     let b = PressState::Pressed {
-        started_pressing_instant: Some(Instant::now().add(Duration::from_secs(342534))),
+        started_pressing_instant: Some(Duration::from_secs(342534)),
     };
     let value = a.cmp(&b);
     assert_eq!(value, std::cmp::Ordering::Less);
 }
 
 /// The current axis state. In other words, the strength (how much the axis is moved) and press state.
+///
+/// The ticks in which the axis was last pressed and released are also stored, so the input view is able
+/// to know whether the press state has changed in the current tick regardless of the frame duration.
 #[derive(PartialEq, Clone, Copy, Debug)]
 pub struct AxisState {
     pub value: f32,
     pub press: PressState,
+    pub pressed_tick: Option<u64>,
+    pub released_tick: Option<u64>,
 }
 
 impl AxisState {
     pub const ZERO: Self = Self {
         value: 0.0,
         press: PressState::Released,
+        pressed_tick: None,
+        released_tick: None,
     };
 
     pub fn new(value: f32, press: PressState) -> Self {
-        Self { value, press }
+        Self {
+            value,
+            press,
+            ..Self::ZERO
+        }
     }
 
     pub fn set(&mut self, value: f32, press: PressState) {
         self.value = value;
         self.press = press;
     }
+
+    /// Set the value and press state for the given tick, recording the tick if the press state has changed.
+    /// If the axis is already pressed, the original press is kept so the pressing duration isn't reset.
+    pub fn update(&mut self, value: f32, press: PressState, tick: u64) {
+        self.value = value;
+        match (self.press.pressed(), press.pressed()) {
+            (false, true) => {
+                self.press = press;
+                self.pressed_tick = Some(tick);
+            }
+            (true, false) => {
+                self.press = press;
+                self.released_tick = Some(tick);
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns whether the axis was pressed in the given tick and is still pressed.
+    #[inline]
+    pub fn just_pressed_on(&self, tick: u64) -> bool {
+        self.press.pressed() && self.pressed_tick == Some(tick)
+    }
+
+    /// Returns whether the axis was released in the given tick and is still released.
+    #[inline]
+    pub fn just_released_on(&self, tick: u64) -> bool {
+        self.press.released() && self.released_tick == Some(tick)
+    }
+
+    /// Returns whether the axis was pressed in the given tick, even if it has been released in the same tick.
+    #[inline]
+    pub fn pressed_on(&self, tick: u64) -> bool {
+        self.pressed_tick == Some(tick)
+    }
 }
 
 impl PressStateExt for AxisState {
@@ -193,8 +230,8 @@ impl PressStateExt for AxisState {
     }
 
     #[inline]
-    fn is_pressed_for(&self, duration: Duration) -> bool {
-        self.press.is_pressed_for(duration)
+    fn is_pressed_for(&self, duration: Duration, now: Duration) -> bool {
+        self.press.is_pressed_for(duration, now)
     }
 
     #[inline]
@@ -208,8 +245,8 @@ impl PressStateExt for AxisState {
     }
 
     #[inline]
-    fn elapsed(&self) -> Option<Duration> {
-        self.press.elapsed()
+    fn elapsed(&self, now: Duration) -> Option<Duration> {
+        self.press.elapsed(now)
     }
 }
 
@@ -0,0 +1,416 @@
+//! Touch screen support for EZInput, including the position and delta of the primary finger, screen regions
+//! acting as virtual buttons and a floating virtual joystick.
+//!
+//! Positions are the ones reported by [`TouchInput`], in logical pixels with the origin at the bottom left of
+//! the window. A finger starting inside the joystick or a virtual button is owned by it until it is lifted;
+//! any other finger may become the primary finger.
+use std::hash::Hash;
+
+use bevy::{
+    input::touch::{TouchInput, TouchPhase},
+    log::warn,
+    math::Vec2,
+    prelude::{Component, EventReader, Query, SystemLabel, Without},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::prelude::*;
+
+#[derive(SystemLabel, Clone, Hash, Debug, PartialEq, Eq)]
+pub struct TouchInputHandlingSystem;
+
+/// All types of axis that can be moved in a touch screen.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug, Deserialize, Serialize)]
+pub enum TouchAxisType {
+    X,
+    Y,
+}
+
+/// Returns whether the given position is inside the region between the given corners.
+#[inline]
+fn in_region(position: Vec2, min: Vec2, max: Vec2) -> bool {
+    position.cmpge(min).all() && position.cmple(max).all()
+}
+
+/// A screen region pressed while a finger that started inside it is touching the screen.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct VirtualButton {
+    pub min: Vec2,
+    pub max: Vec2,
+    finger: Option<u64>,
+}
+
+impl VirtualButton {
+    /// Creates a virtual button for the region between the given corners.
+    pub fn new(min: Vec2, max: Vec2) -> Self {
+        Self {
+            min,
+            max,
+            finger: None,
+        }
+    }
+
+    /// Returns whether the given position is inside this button.
+    pub fn contains(&self, position: Vec2) -> bool {
+        in_region(position, self.min, self.max)
+    }
+}
+
+/// A joystick centered wherever a finger starts touching inside its region. The value is the distance of the
+/// finger from that center divided by the radius, limited to the unit circle.
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct VirtualJoystick {
+    pub min: Vec2,
+    pub max: Vec2,
+    pub radius: f32,
+    origin: Option<Vec2>,
+    finger: Option<u64>,
+}
+
+impl VirtualJoystick {
+    /// Creates a virtual joystick activated inside the region between the given corners.
+    pub fn new(min: Vec2, max: Vec2, radius: f32) -> Self {
+        Self {
+            min,
+            max,
+            radius,
+            origin: None,
+            finger: None,
+        }
+    }
+
+    /// Returns the center of the joystick while it is held.
+    pub fn origin(&self) -> Option<Vec2> {
+        self.origin
+    }
+}
+
+/// Touch screen support for EZInput.
+#[derive(PartialEq, Debug, Component, Clone, Default)]
+pub struct TouchMarker {
+    /// Virtual buttons, bound with [`InputReceiver::TouchButton`] using their index.
+    pub buttons: Vec<VirtualButton>,
+    /// The joystick, bound with [`InputReceiver::TouchJoystick`].
+    pub joystick: Option<VirtualJoystick>,
+    pub primary_finger: Option<u64>,
+    pub touch_position: Option<Vec2>,
+    pub touch_delta: Option<Vec2>,
+    pub does_touch_location_changed_this_tick: bool,
+}
+
+impl TouchMarker {
+    /// Add a virtual button to this marker. Only the first 256 buttons can be bound, since
+    /// [`InputReceiver::TouchButton`] stores the index in a byte; the other ones are never pressed.
+    pub fn with_button(mut self, button: VirtualButton) -> Self {
+        if u8::try_from(self.buttons.len()).is_err() {
+            warn!(
+                "touch button {} can't be bound and will be ignored",
+                self.buttons.len()
+            );
+        }
+        self.buttons.push(button);
+        self
+    }
+
+    /// Set the virtual joystick of this marker.
+    pub fn with_joystick(mut self, joystick: VirtualJoystick) -> Self {
+        self.joystick = Some(joystick);
+        self
+    }
+
+    /// Handle a touch event, updating the receivers of the finger and setting the last input source to Touch.
+    pub fn handle_touch<Keys>(&mut self, view: &mut InputView<Keys>, ev: &TouchInput)
+    where
+        Keys: BindingTypeView,
+    {
+        view.last_input_source = Some(InputSource::Touch);
+        match ev.phase {
+            TouchPhase::Started => self.start_finger(view, ev.id, ev.position),
+            TouchPhase::Moved => self.move_finger(view, ev.id, ev.position),
+            TouchPhase::Ended | TouchPhase::Cancelled => self.end_finger(view, ev.id),
+        }
+    }
+
+    fn start_finger<Keys>(&mut self, view: &mut InputView<Keys>, finger: u64, position: Vec2)
+    where
+        Keys: BindingTypeView,
+    {
+        let pressed = PressState::Pressed {
+            started_pressing_instant: None,
+        };
+        if let Some(joystick) = self.joystick.as_mut() {
+            if joystick.finger.is_none() && in_region(position, joystick.min, joystick.max) {
+                joystick.finger = Some(finger);
+                joystick.origin = Some(position);
+                Self::set_joystick_value(view, Vec2::ZERO, pressed);
+                return;
+            }
+        }
+        if let Some((index, button)) = self
+            .buttons
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(index, button)| Some((u8::try_from(index).ok()?, button)))
+            .find(|(_, button)| button.finger.is_none() && button.contains(position))
+        {
+            button.finger = Some(finger);
+            view.set_key_receiver_state(InputReceiver::TouchButton(index), pressed);
+            return;
+        }
+        if self.primary_finger.is_none() {
+            self.primary_finger = Some(finger);
+            view.set_key_receiver_state(InputReceiver::TouchPress, pressed);
+            self.set_touch_location(view, position, Vec2::ZERO);
+        }
+    }
+
+    fn move_finger<Keys>(&mut self, view: &mut InputView<Keys>, finger: u64, position: Vec2)
+    where
+        Keys: BindingTypeView,
+    {
+        if let Some(joystick) = self
+            .joystick
+            .filter(|joystick| joystick.finger == Some(finger))
+        {
+            let origin = joystick.origin.unwrap_or(position);
+            let value =
+                ((position - origin) / joystick.radius.max(f32::EPSILON)).clamp_length_max(1.);
+            let state = PressState::Pressed {
+                started_pressing_instant: None,
+            };
+            Self::set_joystick_value(view, value, state);
+        } else if self.primary_finger == Some(finger) {
+            let delta = position - self.touch_position.unwrap_or(position);
+            self.set_touch_location(view, position, delta);
+        }
+    }
+
+    fn end_finger<Keys>(&mut self, view: &mut InputView<Keys>, finger: u64)
+    where
+        Keys: BindingTypeView,
+    {
+        if let Some(joystick) = self.joystick.as_mut() {
+            if joystick.finger == Some(finger) {
+                joystick.finger = None;
+                joystick.origin = None;
+                Self::set_joystick_value(view, Vec2::ZERO, PressState::Released);
+                return;
+            }
+        }
+        if let Some((index, button)) = self
+            .buttons
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(index, button)| Some((u8::try_from(index).ok()?, button)))
+            .find(|(_, button)| button.finger == Some(finger))
+        {
+            button.finger = None;
+            view.set_key_receiver_state(InputReceiver::TouchButton(index), PressState::Released);
+            return;
+        }
+        if self.primary_finger == Some(finger) {
+            self.primary_finger = None;
+            view.set_key_receiver_state(InputReceiver::TouchPress, PressState::Released);
+            for axis in [TouchAxisType::X, TouchAxisType::Y] {
+                let value = view.state(&InputReceiver::TouchAxis(axis)).value;
+                view.set_axis_value(InputReceiver::TouchAxis(axis), value, PressState::Released);
+                view.set_axis_value(
+                    InputReceiver::TouchAxisDelta(axis),
+                    0.,
+                    PressState::Released,
+                );
+            }
+            self.touch_delta = None;
+        }
+    }
+
+    fn set_touch_location<Keys>(&mut self, view: &mut InputView<Keys>, position: Vec2, delta: Vec2)
+    where
+        Keys: BindingTypeView,
+    {
+        let state = PressState::Pressed {
+            started_pressing_instant: None,
+        };
+        view.set_axis_value(
+            InputReceiver::TouchAxis(TouchAxisType::X),
+            position.x,
+            state,
+        );
+        view.set_axis_value(
+            InputReceiver::TouchAxis(TouchAxisType::Y),
+            position.y,
+            state,
+        );
+        view.set_axis_value(
+            InputReceiver::TouchAxisDelta(TouchAxisType::X),
+            delta.x,
+            state,
+        );
+        view.set_axis_value(
+            InputReceiver::TouchAxisDelta(TouchAxisType::Y),
+            delta.y,
+            state,
+        );
+        self.touch_position = Some(position);
+        self.touch_delta = Some(delta);
+        self.does_touch_location_changed_this_tick = true;
+    }
+
+    fn set_joystick_value<Keys>(view: &mut InputView<Keys>, value: Vec2, state: PressState)
+    where
+        Keys: BindingTypeView,
+    {
+        view.set_axis_value(
+            InputReceiver::TouchJoystick(TouchAxisType::X),
+            value.x,
+            state,
+        );
+        view.set_axis_value(
+            InputReceiver::TouchJoystick(TouchAxisType::Y),
+            value.y,
+            state,
+        );
+    }
+
+    /// Handle the touch events of the current tick, then tick the touch screen.
+    pub fn handle_events<Keys>(&mut self, view: &mut InputView<Keys>, events: &[TouchInput])
+    where
+        Keys: BindingTypeView,
+    {
+        self.does_touch_location_changed_this_tick = false;
+        for ev in events.iter() {
+            self.handle_touch(view, ev);
+        }
+        self.tick_touch(view);
+    }
+
+    /// Tick the touch screen by releasing the delta of the primary finger if it hasn't moved in the current
+    /// tick. This needs to be called after all touch events for the tick are handled.
+    pub fn tick_touch<Keys>(&mut self, view: &mut InputView<Keys>)
+    where
+        Keys: BindingTypeView,
+    {
+        if !self.does_touch_location_changed_this_tick {
+            for axis in [TouchAxisType::X, TouchAxisType::Y] {
+                view.set_axis_value(
+                    InputReceiver::TouchAxisDelta(axis),
+                    0.,
+                    PressState::Released,
+                );
+            }
+            self.touch_delta = None;
+        }
+    }
+}
+
+/// Input system responsible for handling touch input and setting the button state for each updated button and axis.
+/// Entities being replayed are skipped.
+pub(crate) fn touch_input_system<Keys>(
+    mut query: Query<(&mut InputView<Keys>, &mut TouchMarker), Without<InputReplay>>,
+    mut rd: EventReader<TouchInput>,
+) where
+    Keys: BindingTypeView,
+{
+    let events: Vec<TouchInput> = rd.iter().copied().collect();
+    for (mut view, mut touch_svc) in query.iter_mut() {
+        touch_svc.handle_events(view.as_mut(), &events);
+    }
+}
+
+// Test to check if fingers are routed to the joystick, the virtual buttons and the primary touch, and if buttons
+// beyond the receiver index range are ignored.
+#[test]
+fn touch_input_test() {
+    use bevy::{
+        ecs::event::Events,
+        prelude::{Stage, SystemStage, World},
+    };
+
+    #[derive(crate::BindingTypeView, PartialEq, Eq, Hash, Clone, Copy, Debug)]
+    enum Action {
+        Move,
+        Jump,
+    }
+    let mut view = InputView::new();
+    view.add_binding(ActionBinding::from(Action::Move).dual_axis(DualAxis::touch_joystick()));
+    view.add_binding(&mut ActionBinding::new_from_vec(
+        Action::Jump,
+        vec![vec![InputReceiver::TouchButton(0)]],
+    ));
+    let marker = TouchMarker::default()
+        .with_joystick(VirtualJoystick::new(Vec2::ZERO, Vec2::new(400., 600.), 50.))
+        .with_button(VirtualButton::new(
+            Vec2::new(700., 0.),
+            Vec2::new(800., 100.),
+        ));
+
+    let mut world = World::new();
+    world.insert_resource(Events::<TouchInput>::default());
+    let entity = world.spawn().insert(view).insert(marker).id();
+    let mut stage = SystemStage::single(touch_input_system::<Action>);
+    let mut tick = |world: &mut World, events: &[(TouchPhase, u64, Vec2)]| {
+        let mut touches = world.resource_mut::<Events<TouchInput>>();
+        for (phase, id, position) in events {
+            touches.send(TouchInput {
+                phase: *phase,
+                position: *position,
+                force: None,
+                id: *id,
+            });
+        }
+        stage.run(world);
+    };
+
+    tick(
+        &mut world,
+        &[
+            (TouchPhase::Started, 1, Vec2::new(100., 100.)),
+            (TouchPhase::Started, 2, Vec2::new(750., 50.)),
+            (TouchPhase::Started, 3, Vec2::new(500., 300.)),
+        ],
+    );
+    let view = world.get::<InputView<Action>>(entity).unwrap();
+    assert!(view.key(&Action::Jump).pressed());
+    assert!(view.state(&InputReceiver::TouchPress).press.pressed());
+    assert_eq!(view.state(&TouchAxisType::X.into()).value, 500.);
+
+    tick(
+        &mut world,
+        &[
+            (TouchPhase::Moved, 1, Vec2::new(200., 100.)),
+            (TouchPhase::Moved, 3, Vec2::new(510., 300.)),
+            (TouchPhase::Ended, 2, Vec2::new(750., 50.)),
+        ],
+    );
+    let view = world.get::<InputView<Action>>(entity).unwrap();
+    assert_eq!(view.axis_pair(&Action::Move), Vec2::new(1., 0.));
+    assert_eq!(
+        view.state(&InputReceiver::TouchAxisDelta(TouchAxisType::X))
+            .value,
+        10.
+    );
+    assert!(view.key(&Action::Jump).released());
+
+    tick(&mut world, &[(TouchPhase::Ended, 1, Vec2::new(200., 100.))]);
+    let view = world.get::<InputView<Action>>(entity).unwrap();
+    assert_eq!(view.axis_pair(&Action::Move), Vec2::ZERO);
+    assert!(view
+        .state(&InputReceiver::TouchAxisDelta(TouchAxisType::X))
+        .press
+        .released());
+
+    let mut marker = (0..257).fold(TouchMarker::default(), |marker, index| {
+        let min = Vec2::splat(index as f32 * 10.);
+        marker.with_button(VirtualButton::new(min, min + 5.))
+    });
+    let mut view = InputView::<Action>::new();
+    let touch = TouchInput {
+        phase: TouchPhase::Started,
+        position: Vec2::splat(2562.),
+        force: None,
+        id: 4,
+    };
+    marker.handle_touch(&mut view, &touch);
+    assert!(view.state(&InputReceiver::TouchButton(0)).press.released());
+    assert!(view.state(&InputReceiver::TouchPress).press.pressed());
+}
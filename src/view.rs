@@ -1,8 +1,11 @@
 //! A view is a object where all input states are stored. It also has useful methods such checking
 //! if a key or axis for a [`BindingTypeView`] is pressed or released by proving the [`PressState`].
-use std::{collections::HashMap};
+use std::collections::HashMap;
 
-use bevy::{prelude::Component, utils::hashbrown::HashSet};
+use bevy::{
+    prelude::Component,
+    utils::{hashbrown::HashSet, Duration},
+};
 
 use crate::prelude::*;
 
@@ -12,6 +15,8 @@ pub enum InputSource {
     Gamepad,
     Keyboard,
     Mouse,
+    Touch,
+    Custom(CustomSource),
 }
 
 #[allow(dead_code)]
@@ -30,6 +35,16 @@ impl InputSource {
     pub fn is_mouse(&self) -> bool {
         *self == InputSource::Mouse
     }
+
+    /// Returns whether this input source is referent to a touch screen.
+    pub fn is_touch(&self) -> bool {
+        *self == InputSource::Touch
+    }
+
+    /// Returns whether this input source is a [`crate::custom::CustomInputSource`].
+    pub fn is_custom(&self) -> bool {
+        matches!(self, InputSource::Custom(_))
+    }
 }
 
 /// A holder for input states and its default value.
@@ -58,8 +73,28 @@ where
     Keys: BindingTypeView,
 {
     pub last_input_source: Option<InputSource>,
+    pub tick: u64,
+    pub time: Duration,
     pub bindings: HashMap<Keys, ActionBinding<Keys>>,
+    pub binding_order: Vec<Keys>,
+    pub conflict_policy: ConflictPolicy,
     pub descriptors: Vec<ReceiverDescriptor>,
+    /// The contexts pushed onto this view, in the order they were pushed.
+    pub contexts: Vec<InputContext<Keys>>,
+    /// The bindings and their order before the first context was pushed.
+    pub(crate) base_bindings: Option<BaseBindings<Keys>>,
+    /// The tick each action was last triggered by one of its [`Sequence`]s.
+    pub(crate) completed_sequences: HashMap<Keys, u64>,
+    /// The last press of each action that wasn't consumed yet.
+    pub(crate) buffered_presses: HashMap<Keys, BufferedPress>,
+    /// The runtime state of the interactions of each action.
+    pub(crate) interaction_states: InteractionStateMap<Keys>,
+    /// The runtime state of the accessibility options of each action.
+    pub(crate) accessibility_states: HashMap<Keys, AccessibilityState>,
+    /// The receiver state changes of the current tick, collected only while this view is recorded.
+    pub(crate) recorded_changes: Option<Vec<RecordedChange>>,
+    /// The action states of the snapshot applied with [`InputView::apply_snapshot`].
+    pub(crate) snapshot_states: Option<SnapshotStates<Keys>>,
 }
 
 impl<Keys> InputView<Keys>
@@ -75,8 +110,20 @@ where
     pub fn with_capacity(capacity: usize) -> Self {
         Self {
             last_input_source: None,
+            tick: 0,
+            time: Duration::ZERO,
             bindings: HashMap::new(),
+            binding_order: Vec::new(),
+            conflict_policy: ConflictPolicy::default(),
             descriptors: Vec::with_capacity(capacity),
+            contexts: Vec::new(),
+            base_bindings: None,
+            completed_sequences: HashMap::new(),
+            buffered_presses: HashMap::new(),
+            interaction_states: InteractionStateMap::default(),
+            accessibility_states: HashMap::new(),
+            recorded_changes: None,
+            snapshot_states: None,
         }
     }
 
@@ -126,13 +173,82 @@ where
         }
     }
 
-    /// Insert a new binding into the storage.
+    /// Insert a new binding into the storage. While contexts are pushed, the binding is added to the base
+    /// bindings below them.
     pub fn add_binding(&mut self, binding: &mut ActionBinding<Keys>) -> &mut Self {
         binding.apply_default_axis_to_all_receivers(self);
+        if let Some((base, order)) = self.base_bindings.as_mut() {
+            if !order.contains(&binding.key) {
+                order.push(binding.key);
+            }
+            base.insert(binding.key, binding.clone());
+            self.apply_contexts();
+            return self;
+        }
+        if !self.binding_order.contains(&binding.key) {
+            self.binding_order.push(binding.key);
+        }
         self.bindings.insert(binding.key, binding.clone());
         self
     }
 
+    /// Set the policy used to resolve conflicting bindings.
+    pub fn with_conflict_policy(&mut self, policy: ConflictPolicy) -> &mut Self {
+        self.conflict_policy = policy;
+        self
+    }
+
+    /// Remove the binding for the given key, resetting the default axis value of every receiver that isn't
+    /// used by other bindings anymore. While contexts are pushed, the binding is removed from the base bindings
+    /// below them.
+    pub fn remove_binding(&mut self, kind: &Keys) -> Option<ActionBinding<Keys>> {
+        if let Some((base, order)) = self.base_bindings.as_mut() {
+            order.retain(|key| key != kind);
+            let binding = base.remove(kind);
+            self.apply_contexts();
+            return binding;
+        }
+        let binding = self.bindings.remove(kind)?;
+        self.binding_order.retain(|key| key != kind);
+        for rcv in binding
+            .input_receivers
+            .iter()
+            .flat_map(|rcvs| rcvs.0.iter())
+        {
+            let in_use = self.bindings.values().any(|other| {
+                other
+                    .input_receivers
+                    .iter()
+                    .any(|rcvs| rcvs.0.contains(rcv))
+            });
+            if !in_use {
+                if let Some(descriptor) = self.descriptor_mut(rcv) {
+                    descriptor.default_axis_value = 0.;
+                }
+            }
+        }
+        Some(binding)
+    }
+
+    /// Replace the binding for the key of the given binding, keeping its position in the binding order, which
+    /// decides the priority of conflicting bindings. The binding is added at the end if there's no binding for
+    /// its key.
+    pub fn replace_binding(&mut self, binding: &mut ActionBinding<Keys>) -> &mut Self {
+        let order = match self.base_bindings.as_ref() {
+            Some((_, order)) => order,
+            None => &self.binding_order,
+        };
+        let index = order.iter().position(|key| *key == binding.key);
+        self.remove_binding(&binding.key);
+        if let Some(index) = index {
+            match self.base_bindings.as_mut() {
+                Some((_, order)) => order.insert(index, binding.key),
+                None => self.binding_order.insert(index, binding.key),
+            }
+        }
+        self.add_binding(binding)
+    }
+
     /// Set the button state for a specific key receiver.
     pub fn state(&self, key: &InputReceiver) -> &AxisState {
         self.descriptor(key)
@@ -142,24 +258,28 @@ where
 
     /// Set the axis state for a specific input receiver.
     pub fn set_axis_value(&mut self, input: InputReceiver, value: f32, element_state: PressState) {
+        let tick = self.tick;
         self.descriptor_or_insert(input)
             .axis
-            .set(value, element_state);
+            .update(value, element_state, tick);
+        self.record_change(input, value, element_state.pressed());
     }
 
     /// Set the axis state for a specific input receiver.
     pub fn set_key_receiver_state(&mut self, input: InputReceiver, state: PressState) {
+        let tick = self.tick;
         let descriptor = self.descriptor_or_insert(input);
         let value = match state {
             PressState::Pressed { .. } => {
                 if descriptor.axis.press.pressed() {
-                   return;
+                    return;
                 }
                 descriptor.default_axis_value
-            },
+            }
             PressState::Released => 0.0,
         };
-        descriptor.axis.set(value, state);
+        descriptor.axis.update(value, state, tick);
+        self.record_change(input, value, state.pressed());
     }
 
     /// Return the current press state for a specific binding matching with the given BindingTypeView.
@@ -167,12 +287,34 @@ where
         self.axis(kind).last().unwrap_or(&AxisState::ZERO).press
     }
 
-    /// Return the current axis state for a specific binding matching with the given BindingTypeView.
+    /// Return the current axis state for a specific binding matching with the given BindingTypeView. The values
+    /// are processed by the [`AxisProcessor`] pipelines of the binding.
     pub fn axis(&self, kind: &Keys) -> Vec<AxisState> {
+        let mut output = self.receiver_axis(kind);
+        if let Some(binding) = self.bindings.get(kind) {
+            // Smoothing follows the action value, which is the value of the last receiver.
+            let last = output.len().saturating_sub(1);
+            for (index, state) in output.iter_mut().enumerate() {
+                state.value = if index == last {
+                    binding.processors.process(state.value)
+                } else {
+                    binding.processors.process_unsmoothed(state.value)
+                };
+            }
+        }
+        output
+    }
+
+    /// Return the current axis state for a specific binding, processed only by its receiver pipelines.
+    pub(crate) fn receiver_axis(&self, kind: &Keys) -> Vec<AxisState> {
         let binding = self.bindings.get(kind);
-        if let Some(binding) = binding {
+        if let Some(state) = self.overridden_state(kind) {
+            if state.press.pressed() {
+                return vec![state];
+            }
+        } else if let Some(binding) = binding {
             'initial: for r in binding.input_receivers.iter() {
-                if r.0.is_empty() {
+                if r.0.is_empty() || self.is_suppressed(kind, r, |s| s.press.pressed()) {
                     continue 'initial;
                 }
                 let states = r.0.iter();
@@ -182,17 +324,105 @@ where
                     if state.press.released() {
                         continue 'initial;
                     }
-                    output.push(*state);
+                    output.push(AxisState {
+                        value: binding.process_receiver(rcv, state.value),
+                        ..*state
+                    });
                 }
                 return output;
             }
         }
-        Vec::new()
+        match self.sequence_state(kind) {
+            Some(state) if state.press.pressed() => vec![state],
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns the elapsed time, measured by the [`crate::clock::InputClock`], since the action for the given key
+    /// was pressed.
+    pub fn elapsed(&self, kind: &Keys) -> Option<Duration> {
+        self.key(kind).elapsed(self.time)
+    }
+
+    /// Returns whether the action for the given key is pressed for more than a specific duration, measured by the
+    /// [`crate::clock::InputClock`].
+    pub fn is_pressed_for(&self, kind: &Keys, duration: Duration) -> bool {
+        self.key(kind).is_pressed_for(duration, self.time)
+    }
+
+    /// Returns whether the action for the given key was pressed in the current tick, that is, one of its
+    /// receiver combinations is fully pressed and at least one of its receivers was pressed in this tick.
+    pub fn just_pressed(&self, kind: &Keys) -> bool {
+        let tick = self.tick;
+        self.any_receivers(kind, |states| {
+            states.iter().all(|s| s.press.pressed())
+                && states.iter().any(|s| s.just_pressed_on(tick))
+        })
+    }
+
+    /// Returns whether the action for the given key was released in the current tick, that is, none of its
+    /// receiver combinations is pressed anymore and at least one of them was held until this tick.
+    pub fn just_released(&self, kind: &Keys) -> bool {
+        let tick = self.tick;
+        !self.any_receivers(kind, |states| states.iter().all(|s| s.press.pressed()))
+            && self.any_receivers(kind, |states| {
+                states
+                    .iter()
+                    .all(|s| s.press.pressed() || s.released_tick == Some(tick))
+                    && states.iter().any(|s| s.just_released_on(tick))
+            })
+    }
+
+    /// Returns whether the action for the given key was pressed at any moment of the current tick, even if it
+    /// was pressed and released again before the tick ended.
+    pub fn pressed_this_frame(&self, kind: &Keys) -> bool {
+        let tick = self.tick;
+        self.any_receivers(kind, |states| {
+            states
+                .iter()
+                .all(|s| s.press.pressed() || s.released_tick == Some(tick))
+                && states.iter().any(|s| s.pressed_on(tick))
+        })
+    }
+
+    /// Returns the state replacing the receiver combinations of the given key: the state of an applied snapshot,
+    /// or the state resulting from the accessibility options of the binding when any is enabled.
+    fn overridden_state(&self, kind: &Keys) -> Option<AxisState> {
+        self.snapshot_state(kind)
+            .or_else(|| self.assisted_state(kind))
+    }
+
+    /// Returns whether any receiver combination or the sequences of the given key satisfy the predicate.
+    /// Combinations suppressed by a conflicting binding active in this tick are skipped, and are replaced by the
+    /// state of an applied snapshot or the accessibility options of the binding, if any.
+    fn any_receivers(&self, kind: &Keys, predicate: impl Fn(&[AxisState]) -> bool) -> bool {
+        let tick = self.tick;
+        let active = |s: &AxisState| s.press.pressed() || s.released_tick == Some(tick);
+        if let Some(state) = self.overridden_state(kind) {
+            return predicate(&[state])
+                || self
+                    .sequence_state(kind)
+                    .is_some_and(|state| predicate(&[state]));
+        }
+        self.bindings.get(kind).is_some_and(|binding| {
+            binding
+                .input_receivers
+                .iter()
+                .filter(|rcvs| !rcvs.0.is_empty() && !self.is_suppressed(kind, rcvs, active))
+                .any(|rcvs| {
+                    let states: Vec<AxisState> =
+                        rcvs.0.iter().map(|rcv| *self.state(rcv)).collect();
+                    predicate(&states)
+                })
+        }) || self
+            .sequence_state(kind)
+            .is_some_and(|state| predicate(&[state]))
     }
 
-    /// A utility function for removing all receivers with a specific source.
+    /// A utility function for removing all receivers with a specific source, from the base bindings as well as
+    /// from every pushed context.
     pub fn clear_from_specific_source(&mut self, source: InputSource) {
-        for binding in self.bindings.values_mut() {
+        let clear = |binding: &mut ActionBinding<Keys>| {
             let mut rcvs_: HashSet<InputReceivers> = HashSet::new();
             for rcvs in binding.input_receivers.iter() {
                 let rcvs: Vec<InputReceiver> = rcvs
@@ -209,7 +439,14 @@ where
             binding
                 .default_axis_value
                 .retain(|k, _| k.source() != source);
+            binding
+                .dual_axes
+                .retain(|dual| dual.receivers().iter().all(|k| k.source() != source));
+        };
+        for context in self.contexts.iter_mut() {
+            context.bindings.iter_mut().for_each(clear);
         }
+        self.edit_base_bindings(|bindings, _| bindings.values_mut().for_each(clear));
         self.descriptors.retain(|dsc| dsc.input.source() != source );
     }
 
@@ -226,7 +463,7 @@ where
     pub fn combine_first<const T: usize>(&self, array: &[&Keys; T]) -> Vec<Option<AxisState>> {
         let mut output = Vec::with_capacity(T);
         for key in array {
-            output.push(self.axis(key).first().map(|x| *x));
+            output.push(self.axis(key).first().copied());
         }
         output
     }
@@ -236,3 +473,42 @@ where
         self.descriptors.retain(|dsc| dsc.default_axis_value != 0. || dsc.axis.press.pressed());
     }
 }
+
+// Test to check if the press edges are reported only in the tick they happened.
+#[test]
+fn press_edges_test() {
+    #[derive(crate::BindingTypeView, PartialEq, Eq, Hash, Clone, Copy, Debug)]
+    enum Action {
+        Jump,
+    }
+    let pressed = PressState::Pressed {
+        started_pressing_instant: None,
+    };
+    let space = InputReceiver::KeyboardKey(bevy::prelude::KeyCode::Space);
+    let mut view = InputView::new();
+    view.add_binding(&mut ActionBinding::new_from_vec(
+        Action::Jump,
+        vec![vec![space]],
+    ));
+
+    view.tick = 1;
+    view.set_key_receiver_state(space, pressed);
+    assert!(view.just_pressed(&Action::Jump));
+    assert!(view.pressed_this_frame(&Action::Jump));
+
+    view.tick = 2;
+    view.set_key_receiver_state(space, pressed);
+    assert!(!view.just_pressed(&Action::Jump));
+    assert!(view.key(&Action::Jump).pressed());
+
+    view.tick = 3;
+    view.set_key_receiver_state(space, PressState::Released);
+    assert!(view.just_released(&Action::Jump));
+
+    view.tick = 4;
+    assert!(!view.just_released(&Action::Jump));
+    view.set_key_receiver_state(space, pressed);
+    view.set_key_receiver_state(space, PressState::Released);
+    assert!(!view.just_pressed(&Action::Jump));
+    assert!(view.pressed_this_frame(&Action::Jump));
+}